@@ -70,6 +70,19 @@ pub fn options(input: TokenStream) -> TokenStream {
         }
     };
 
+    // Streaming operations are long-lived and come with their own reconnection logic.
+    let retry_policy = if is_streaming {
+        quote! {}
+    } else {
+        quote! {
+            /// Overrides the client retry policy for this command.
+            pub fn retry_policy(mut self, retry_policy: crate::options::retry::RetryPolicy) -> Self {
+                self.common_operation_options.retry_policy = Some(retry_policy);
+                self
+            }
+        }
+    };
+
     let attrs = input.attrs.iter().map(|attr| quote! { #attr });
     let vis = &input.visibility;
     let name = input.name;
@@ -98,6 +111,13 @@ pub fn options(input: TokenStream) -> TokenStream {
                 self
             }
 
+            /// Performs the command with the TLS client certificate registered under the given
+            /// name on the client.
+            pub fn tls_identity(mut self, name: impl Into<String>) -> Self {
+                self.common_operation_options.tls_identity = Some(name.into());
+                self
+            }
+
             pub fn requires_leader(mut self, requires_leader: bool) -> Self {
                 self.common_operation_options.requires_leader = requires_leader;
                 self
@@ -107,6 +127,8 @@ pub fn options(input: TokenStream) -> TokenStream {
                 self.common_operation_options.deadline = Some(deadline);
                 self
             }
+
+            #retry_policy
         }
     })
 }
@@ -3,6 +3,7 @@
 #![allow(unused_results)]
 #![allow(unused_variables)]
 #![allow(unreachable_code)]
+#![allow(dead_code)]
 
 use futures::TryStreamExt;
 use kurrentdb::{
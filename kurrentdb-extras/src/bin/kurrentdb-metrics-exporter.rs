@@ -0,0 +1,48 @@
+//! Serves the statistics of a KurrentDB server in the Prometheus text format.
+//!
+//! ```text
+//! kurrentdb-metrics-exporter <connection-string> [listen-address]
+//! ```
+//!
+//! Metrics are served on `GET /metrics`, at `127.0.0.1:9184` by default.
+//!
+//! Built with the `cli` feature.
+use std::process::ExitCode;
+
+use kurrentdb::operations;
+use kurrentdb_extras::metrics::{ExporterOptions, MetricsExporter};
+
+const USAGE: &str = "usage: kurrentdb-metrics-exporter <connection-string> [listen-address]";
+const DEFAULT_ADDRESS: &str = "127.0.0.1:9184";
+
+#[tokio::main]
+async fn main() -> ExitCode {
+    let args = std::env::args().skip(1).collect::<Vec<_>>();
+
+    let (connection_string, address) = match args.as_slice() {
+        [connection_string] => (connection_string.as_str(), DEFAULT_ADDRESS),
+        [connection_string, address] => (connection_string.as_str(), address.as_str()),
+        _ => {
+            eprintln!("{}", USAGE);
+            return ExitCode::FAILURE;
+        }
+    };
+
+    match run(connection_string, address).await {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("error: {}", e);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+async fn run(connection_string: &str, address: &str) -> Result<(), Box<dyn std::error::Error>> {
+    let client = operations::Client::new(connection_string.parse()?);
+    let exporter = MetricsExporter::spawn(client, ExporterOptions::default());
+
+    eprintln!("serving metrics on http://{}/metrics", address);
+    exporter.serve(address).await?;
+
+    Ok(())
+}
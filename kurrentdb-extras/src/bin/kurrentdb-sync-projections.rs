@@ -0,0 +1,68 @@
+//! Syncs a directory of JavaScript projections to a KurrentDB server.
+//!
+//! ```text
+//! kurrentdb-sync-projections <connection-string> <directory> [--dry-run] [--prune]
+//! ```
+//!
+//! * `--dry-run`: prints the changes without performing them.
+//! * `--prune`: deletes the projections that have no file in the directory.
+//!
+//! Built with the `cli` feature.
+use std::process::ExitCode;
+
+use kurrentdb::ProjectionClient;
+use kurrentdb_extras::projections;
+
+const USAGE: &str =
+    "usage: kurrentdb-sync-projections <connection-string> <directory> [--dry-run] [--prune]";
+
+#[tokio::main]
+async fn main() -> ExitCode {
+    let mut positional = Vec::new();
+    let mut dry_run = false;
+    let mut prune = false;
+
+    for arg in std::env::args().skip(1) {
+        match arg.as_str() {
+            "--dry-run" => dry_run = true,
+            "--prune" => prune = true,
+            flag if flag.starts_with("--") => {
+                eprintln!("unknown option '{}'\n{}", flag, USAGE);
+                return ExitCode::FAILURE;
+            }
+            _ => positional.push(arg),
+        }
+    }
+
+    let [connection_string, directory] = positional.as_slice() else {
+        eprintln!("{}", USAGE);
+        return ExitCode::FAILURE;
+    };
+
+    match run(connection_string, directory, dry_run, prune).await {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("error: {}", e);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+async fn run(
+    connection_string: &str,
+    directory: &str,
+    dry_run: bool,
+    prune: bool,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let desired = projections::load_dir(directory)?;
+    let client = ProjectionClient::new(connection_string.parse()?);
+    let plan = projections::plan(&client, &desired, prune).await?;
+
+    print!("{}", plan);
+
+    if !dry_run {
+        projections::apply(&client, &plan).await?;
+    }
+
+    Ok(())
+}
@@ -1,3 +1,5 @@
 #[macro_use]
 extern crate log;
+pub mod metrics;
+pub mod projections;
 pub mod stats;
@@ -0,0 +1,596 @@
+//! Prometheus exporter for the server statistics.
+//!
+//! [`render`] turns a [`Statistics`] sample into the Prometheus text exposition format, to embed
+//! in an existing `/metrics` endpoint. [`MetricsExporter`] keeps polling the server in the
+//! background and can serve the latest sample on its own HTTP endpoint.
+//!
+//! Every sample is labelled with the node it comes from, queue metrics also carry the queue and
+//! group names.
+use std::fmt::Write as _;
+use std::sync::{Arc, RwLock};
+use std::time::Duration;
+
+use kurrentdb::operations::{self, Statistics, StatsOptions};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream, ToSocketAddrs};
+use tokio::task::JoinHandle;
+
+/// Content type of the text rendered by [`render`].
+pub const CONTENT_TYPE: &str = "text/plain; version=0.0.4; charset=utf-8";
+
+const MAX_REQUEST_SIZE: usize = 8 * 1_024;
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+const ACCEPT_ERROR_DELAY: Duration = Duration::from_secs(1);
+
+/// Renders a statistics sample of `node` in the Prometheus text exposition format.
+pub fn render(stats: &Statistics, node: &str) -> String {
+    let mut out = Exposition::new(node);
+    let proc = &stats.proc;
+
+    if let Some(start_time) = proc.start_time {
+        out.gauge(
+            "kurrentdb_proc_start_time_seconds",
+            "Start time of the server process, since the Unix epoch.",
+        )
+        .sample(&[], start_time.timestamp() as f64);
+    }
+
+    out.gauge(
+        "kurrentdb_proc_memory_bytes",
+        "Memory used by the server process.",
+    )
+    .sample(&[], proc.mem as f64);
+    out.gauge(
+        "kurrentdb_proc_cpu_percent",
+        "CPU usage of the server process.",
+    )
+    .sample(&[], proc.cpu);
+    out.gauge("kurrentdb_proc_threads", "Threads of the server process.")
+        .sample(&[], proc.threads_count as f64);
+    out.gauge(
+        "kurrentdb_proc_thrown_exceptions_per_second",
+        "Exceptions thrown by the server process.",
+    )
+    .sample(&[], proc.thrown_exceptions_rate);
+    out.gauge(
+        "kurrentdb_proc_contentions_per_second",
+        "Lock contentions of the server process.",
+    )
+    .sample(&[], proc.contentions_rate);
+
+    let gc = &proc.gc;
+    out.gauge(
+        "kurrentdb_gc_allocation_bytes_per_second",
+        "Memory allocation speed.",
+    )
+    .sample(&[], gc.allocation_speed);
+    out.counter(
+        "kurrentdb_gc_collections_total",
+        "Garbage collections, by generation.",
+    )
+    .sample(&[("generation", "0")], gc.gen0_items_count as f64)
+    .sample(&[("generation", "1")], gc.gen1_items_count as f64)
+    .sample(&[("generation", "2")], gc.gen2_items_count as f64);
+    out.gauge(
+        "kurrentdb_gc_generation_size_bytes",
+        "Size of each garbage collector generation.",
+    )
+    .sample(&[("generation", "0")], gc.gen0_size as f64)
+    .sample(&[("generation", "1")], gc.gen1_size as f64)
+    .sample(&[("generation", "2")], gc.gen2_size as f64);
+    out.gauge(
+        "kurrentdb_gc_large_object_heap_bytes",
+        "Size of the large object heap.",
+    )
+    .sample(&[], gc.large_heap_size as f64);
+    out.gauge(
+        "kurrentdb_gc_heap_bytes",
+        "Total size of the managed heaps.",
+    )
+    .sample(&[], gc.total_bytes_in_heaps as f64);
+    out.gauge(
+        "kurrentdb_gc_time_percent",
+        "Share of time spent in garbage collection.",
+    )
+    .sample(&[], gc.time_in_gc);
+
+    let io = &proc.disk_io;
+    out.counter("kurrentdb_disk_read_bytes_total", "Bytes read from disk.")
+        .sample(&[], io.read_bytes as f64);
+    out.counter(
+        "kurrentdb_disk_written_bytes_total",
+        "Bytes written to disk.",
+    )
+    .sample(&[], io.written_bytes as f64);
+    out.counter("kurrentdb_disk_read_ops_total", "Disk read operations.")
+        .sample(&[], io.read_ops as f64);
+    out.counter("kurrentdb_disk_write_ops_total", "Disk write operations.")
+        .sample(&[], io.write_ops as f64);
+
+    let tcp = &proc.tcp;
+    out.gauge("kurrentdb_tcp_connections", "Open TCP connections.")
+        .sample(&[], tcp.connections as f64);
+    out.counter(
+        "kurrentdb_tcp_received_bytes_total",
+        "Bytes received over TCP.",
+    )
+    .sample(&[], tcp.received_bytes_total as f64);
+    out.counter("kurrentdb_tcp_sent_bytes_total", "Bytes sent over TCP.")
+        .sample(&[], tcp.sent_bytes_total as f64);
+    out.gauge(
+        "kurrentdb_tcp_receiving_bytes_per_second",
+        "TCP receiving speed.",
+    )
+    .sample(&[], tcp.receiving_speed);
+    out.gauge(
+        "kurrentdb_tcp_sending_bytes_per_second",
+        "TCP sending speed.",
+    )
+    .sample(&[], tcp.sending_speed);
+    out.gauge(
+        "kurrentdb_tcp_pending_send_bytes",
+        "Bytes waiting to be sent.",
+    )
+    .sample(&[], tcp.pending_send as f64);
+    out.gauge(
+        "kurrentdb_tcp_pending_received_bytes",
+        "Bytes received and waiting to be processed.",
+    )
+    .sample(&[], tcp.pending_received as f64);
+
+    let sys = &stats.sys;
+    out.gauge(
+        "kurrentdb_sys_free_memory_bytes",
+        "Free memory of the host.",
+    )
+    .sample(&[], sys.free_mem as f64);
+    out.gauge("kurrentdb_sys_load_average", "Load average of the host.")
+        .sample(&[("period", "1m")], sys.loadavg.one_m)
+        .sample(&[("period", "5m")], sys.loadavg.five_m)
+        .sample(&[("period", "15m")], sys.loadavg.fifteen_m);
+
+    let mut family = out.gauge(
+        "kurrentdb_drive_available_bytes",
+        "Available space of the database drive.",
+    );
+    for (path, drive) in sys.drives.iter() {
+        family.sample(&[("drive", path)], drive.available_bytes as f64);
+    }
+
+    let mut family = out.gauge(
+        "kurrentdb_drive_total_bytes",
+        "Total space of the database drive.",
+    );
+    for (path, drive) in sys.drives.iter() {
+        family.sample(&[("drive", path)], drive.total_bytes as f64);
+    }
+
+    let mut family = out.gauge(
+        "kurrentdb_drive_used_bytes",
+        "Used space of the database drive.",
+    );
+    for (path, drive) in sys.drives.iter() {
+        family.sample(&[("drive", path)], drive.used_bytes as f64);
+    }
+
+    type QueueMetric = (
+        &'static str,
+        &'static str,
+        bool,
+        fn(&operations::Queue) -> f64,
+    );
+    let queue_metrics: [QueueMetric; 6] = [
+        (
+            "kurrentdb_queue_length",
+            "Messages waiting in the queue.",
+            false,
+            |q| q.length as f64,
+        ),
+        (
+            "kurrentdb_queue_length_lifetime_peak",
+            "Longest length the queue reached.",
+            false,
+            |q| q.length_lifetime_peak as f64,
+        ),
+        (
+            "kurrentdb_queue_processed_total",
+            "Messages processed by the queue.",
+            true,
+            |q| q.total_items_processed as f64,
+        ),
+        (
+            "kurrentdb_queue_items_per_second",
+            "Average messages processed per second.",
+            false,
+            |q| q.avg_items_per_second,
+        ),
+        (
+            "kurrentdb_queue_processing_time_ms",
+            "Average time spent processing a message.",
+            false,
+            |q| q.avg_processing_time,
+        ),
+        (
+            "kurrentdb_queue_idle_time_percent",
+            "Share of time the queue was idle.",
+            false,
+            |q| q.idle_time_percent,
+        ),
+    ];
+
+    for (name, help, is_counter, value) in queue_metrics {
+        let mut family = if is_counter {
+            out.counter(name, help)
+        } else {
+            out.gauge(name, help)
+        };
+
+        for (key, queue) in stats.es.queues.iter() {
+            let queue_name = if queue.name.is_empty() {
+                key.as_str()
+            } else {
+                queue.name.as_str()
+            };
+
+            family.sample(
+                &[("queue", queue_name), ("group", queue.group_name.as_str())],
+                value(queue),
+            );
+        }
+    }
+
+    let writer = &stats.es.writer;
+    out.gauge(
+        "kurrentdb_writer_flush_size_bytes",
+        "Size of writer flushes.",
+    )
+    .sample(&[("stat", "last")], writer.last_flush_size as f64)
+    .sample(&[("stat", "mean")], writer.mean_flush_size as f64)
+    .sample(&[("stat", "max")], writer.max_flush_size as f64);
+    out.gauge(
+        "kurrentdb_writer_flush_delay_ms",
+        "Delay of writer flushes.",
+    )
+    .sample(&[("stat", "last")], writer.last_flush_delay_ms)
+    .sample(&[("stat", "mean")], writer.mean_flush_delay_ms)
+    .sample(&[("stat", "max")], writer.max_flush_delay_ms);
+    out.gauge(
+        "kurrentdb_writer_queued_flush_messages",
+        "Messages waiting to be flushed.",
+    )
+    .sample(&[], writer.queued_flush_messages as f64);
+    out.gauge(
+        "kurrentdb_writer_checkpoint",
+        "Position of the writer checkpoint.",
+    )
+    .sample(&[("state", "flushed")], stats.es.checksum as f64)
+    .sample(
+        &[("state", "non_flushed")],
+        stats.es.checksum_non_flushed as f64,
+    );
+
+    out.finish()
+}
+
+struct Exposition {
+    node: String,
+    text: String,
+}
+
+impl Exposition {
+    fn new(node: &str) -> Self {
+        Self {
+            node: escape_label(node),
+            text: String::new(),
+        }
+    }
+
+    fn gauge(&mut self, name: &'static str, help: &str) -> Family<'_> {
+        self.family(name, help, "gauge")
+    }
+
+    fn counter(&mut self, name: &'static str, help: &str) -> Family<'_> {
+        self.family(name, help, "counter")
+    }
+
+    fn family(&mut self, name: &'static str, help: &str, kind: &str) -> Family<'_> {
+        let _ = writeln!(self.text, "# HELP {} {}", name, help);
+        let _ = writeln!(self.text, "# TYPE {} {}", name, kind);
+
+        Family { out: self, name }
+    }
+
+    fn finish(self) -> String {
+        self.text
+    }
+}
+
+struct Family<'a> {
+    out: &'a mut Exposition,
+    name: &'static str,
+}
+
+impl Family<'_> {
+    fn sample(&mut self, labels: &[(&str, &str)], value: f64) -> &mut Self {
+        let text = &mut self.out.text;
+        let _ = write!(text, "{}{{node=\"{}\"", self.name, self.out.node);
+
+        for (key, label) in labels {
+            let _ = write!(text, ",{}=\"{}\"", key, escape_label(label));
+        }
+
+        let _ = writeln!(text, "}} {}", format_value(value));
+
+        self
+    }
+}
+
+fn escape_label(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+fn format_value(value: f64) -> String {
+    if value.is_nan() {
+        "NaN".to_string()
+    } else if value.is_infinite() {
+        if value > 0.0 { "+Inf" } else { "-Inf" }.to_string()
+    } else {
+        value.to_string()
+    }
+}
+
+/// Options of [`MetricsExporter::spawn`].
+#[derive(Clone, Debug)]
+pub struct ExporterOptions {
+    pub refresh_time: Duration,
+    pub retry_delay: Duration,
+    pub node: Option<String>,
+}
+
+impl Default for ExporterOptions {
+    fn default() -> Self {
+        Self {
+            refresh_time: Duration::from_secs(5),
+            retry_delay: Duration::from_secs(5),
+            node: None,
+        }
+    }
+}
+
+impl ExporterOptions {
+    /// How often the server sends statistics. Default is 5 seconds.
+    pub fn refresh_time(self, refresh_time: Duration) -> Self {
+        Self {
+            refresh_time,
+            ..self
+        }
+    }
+
+    /// Delay before reconnecting after the statistics stream failed. Default is 5 seconds.
+    pub fn retry_delay(self, retry_delay: Duration) -> Self {
+        Self {
+            retry_delay,
+            ..self
+        }
+    }
+
+    /// Value of the `node` label. Defaults to the `host:port` of the node sending the
+    /// statistics.
+    pub fn node(self, node: impl Into<String>) -> Self {
+        Self {
+            node: Some(node.into()),
+            ..self
+        }
+    }
+}
+
+/// Polls the server statistics in the background and keeps the latest sample rendered. The
+/// polling stops once every clone is dropped.
+#[derive(Clone)]
+pub struct MetricsExporter {
+    latest: Arc<RwLock<Option<String>>>,
+    _task: Arc<AbortOnDrop>,
+}
+
+struct AbortOnDrop(JoinHandle<()>);
+
+impl Drop for AbortOnDrop {
+    fn drop(&mut self) {
+        self.0.abort();
+    }
+}
+
+impl MetricsExporter {
+    /// Starts polling. Must be called from within a Tokio runtime.
+    pub fn spawn(client: operations::Client, options: ExporterOptions) -> Self {
+        let latest = Arc::new(RwLock::new(None));
+        let task = tokio::spawn(poll(client, options, latest.clone()));
+
+        Self {
+            latest,
+            _task: Arc::new(AbortOnDrop(task)),
+        }
+    }
+
+    /// The latest sample in the Prometheus text format, `None` until the first one arrives and
+    /// while the statistics stream is down, so a stale sample is never served.
+    pub fn render(&self) -> Option<String> {
+        self.latest.read().ok()?.clone()
+    }
+
+    /// Serves the latest sample on `GET /metrics`. Only fails if `addr` can't be bound, failures
+    /// to accept a connection, like running out of file descriptors, are logged and the server
+    /// keeps going.
+    pub async fn serve(&self, addr: impl ToSocketAddrs) -> std::io::Result<()> {
+        let listener = TcpListener::bind(addr).await?;
+
+        loop {
+            let (stream, peer) = match listener.accept().await {
+                Ok(accepted) => accepted,
+                Err(e) if is_connection_error(&e) => continue,
+                Err(e) => {
+                    // Usually resource exhaustion, give the server some time to recover.
+                    warn!("Failed to accept a metrics connection: {}", e);
+                    tokio::time::sleep(ACCEPT_ERROR_DELAY).await;
+                    continue;
+                }
+            };
+
+            let exporter = self.clone();
+
+            tokio::spawn(async move {
+                if let Err(e) = exporter.respond(stream).await {
+                    debug!("Metrics request from {} failed: {}", peer, e);
+                }
+            });
+        }
+    }
+
+    async fn respond(&self, mut stream: TcpStream) -> std::io::Result<()> {
+        // Idle connections would otherwise hold their task forever.
+        let request = tokio::time::timeout(REQUEST_TIMEOUT, read_request(&mut stream)).await??;
+
+        let Some(request) = request else {
+            return Ok(());
+        };
+
+        let request_line = String::from_utf8_lossy(&request);
+        let mut parts = request_line.split_whitespace();
+        let target = (parts.next(), parts.next());
+
+        let (status, content_type, body) = match target {
+            (Some("GET"), Some("/metrics")) => match self.render() {
+                Some(body) => ("200 OK", CONTENT_TYPE, body),
+                None => (
+                    "503 Service Unavailable",
+                    "text/plain",
+                    "no statistics available\n".to_string(),
+                ),
+            },
+
+            _ => ("404 Not Found", "text/plain", "not found\n".to_string()),
+        };
+
+        let response = format!(
+            "HTTP/1.1 {}\r\nContent-Type: {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+            status,
+            content_type,
+            body.len(),
+            body
+        );
+
+        stream.write_all(response.as_bytes()).await?;
+        stream.shutdown().await
+    }
+}
+
+/// Reads the request head, `None` if the connection closed or the head is too large.
+async fn read_request(stream: &mut TcpStream) -> std::io::Result<Option<Vec<u8>>> {
+    let mut request = Vec::new();
+    let mut buf = [0u8; 1_024];
+
+    while !request.windows(4).any(|w| w == b"\r\n\r\n") {
+        let read = stream.read(&mut buf).await?;
+
+        if read == 0 || request.len() + read > MAX_REQUEST_SIZE {
+            return Ok(None);
+        }
+
+        request.extend_from_slice(&buf[..read]);
+    }
+
+    Ok(Some(request))
+}
+
+/// Errors about a single connection, which went away before it could be accepted.
+fn is_connection_error(e: &std::io::Error) -> bool {
+    matches!(
+        e.kind(),
+        std::io::ErrorKind::ConnectionRefused
+            | std::io::ErrorKind::ConnectionAborted
+            | std::io::ErrorKind::ConnectionReset
+            | std::io::ErrorKind::Interrupted
+    )
+}
+
+async fn poll(
+    client: operations::Client,
+    options: ExporterOptions,
+    latest: Arc<RwLock<Option<String>>>,
+) {
+    let stats_options = StatsOptions::default().refresh_time(options.refresh_time);
+
+    loop {
+        if let Err(e) = poll_until_error(&client, &options, &stats_options, &latest).await {
+            warn!("Statistics stream failed, reconnecting: {}", e);
+        }
+
+        if let Ok(mut latest) = latest.write() {
+            *latest = None;
+        }
+
+        tokio::time::sleep(options.retry_delay).await;
+    }
+}
+
+async fn poll_until_error(
+    client: &operations::Client,
+    options: &ExporterOptions,
+    stats_options: &StatsOptions,
+    latest: &RwLock<Option<String>>,
+) -> kurrentdb::Result<()> {
+    let mut stats = client.stats(stats_options).await?;
+    let node = match options.node.clone() {
+        Some(node) => node,
+        None => format!("{}:{}", stats.node().host, stats.node().port),
+    };
+
+    loop {
+        let text = render(&stats.next().await?.to_statistics(), node.as_str());
+
+        if let Ok(mut latest) = latest.write() {
+            *latest = Some(text);
+        }
+    }
+}
+
+#[cfg(test)]
+mod metrics_tests {
+    use super::*;
+    use kurrentdb::operations::RawStatistics;
+
+    #[test]
+    fn test_render() {
+        let stats = RawStatistics(
+            [
+                ("proc-mem", "1024"),
+                ("proc-diskIo-writtenBytes", "2048"),
+                ("es-queue-Worker #1-queueName", "Worker #1"),
+                ("es-queue-Worker #1-groupName", "Workers"),
+                ("es-queue-Worker #1-length", "3"),
+                ("sys-loadavg-1m", "0.5"),
+            ]
+            .into_iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect(),
+        )
+        .to_statistics();
+
+        let text = render(&stats, "node \"a\"");
+
+        assert!(text.contains("# TYPE kurrentdb_disk_written_bytes_total counter\n"));
+        assert!(text.contains("kurrentdb_proc_memory_bytes{node=\"node \\\"a\\\"\"} 1024\n"));
+        assert!(text.contains(
+            "kurrentdb_queue_length{node=\"node \\\"a\\\"\",queue=\"Worker #1\",group=\"Workers\"} 3\n"
+        ));
+        assert!(
+            text.contains(
+                "kurrentdb_sys_load_average{node=\"node \\\"a\\\"\",period=\"1m\"} 0.5\n"
+            )
+        );
+        assert!(!text.contains("kurrentdb_proc_start_time_seconds"));
+    }
+}
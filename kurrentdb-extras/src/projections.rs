@@ -0,0 +1,403 @@
+//! Declarative deployment of continuous projections.
+//!
+//! Describe the projections a database should have with [`DesiredProjection`], then compute the
+//! changes needed to get there with [`plan`] and perform them with [`apply`]. [`load_dir`] builds
+//! the desired set from a directory of JavaScript files.
+//!
+//! Only continuous projections are managed. System projections, whose name starts with `$`, are
+//! never touched.
+use std::fmt::{self, Display, Formatter};
+use std::path::Path;
+
+use futures::TryStreamExt;
+use kurrentdb::{
+    CreateProjectionOptions, DeleteProjectionOptions, GenericProjectionOptions, ProjectionClient,
+    ProjectionDefinition, UpdateProjectionOptions,
+};
+
+/// Comment line prefix holding the settings of a projection file, e.g.
+/// `// kurrentdb: emit=true, track_emitted_streams=true, enabled=false`.
+const DIRECTIVE_PREFIX: &str = "// kurrentdb:";
+
+/// A projection as it should exist on the server.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DesiredProjection {
+    pub name: String,
+    pub query: String,
+    pub emit: bool,
+    /// Only applied when the projection is created, the server doesn't allow changing it later.
+    pub track_emitted_streams: bool,
+    pub enabled: bool,
+}
+
+impl DesiredProjection {
+    /// An enabled projection that doesn't emit events.
+    pub fn new(name: impl Into<String>, query: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            query: query.into(),
+            emit: false,
+            track_emitted_streams: false,
+            enabled: true,
+        }
+    }
+
+    pub fn emit(self, emit: bool) -> Self {
+        Self { emit, ..self }
+    }
+
+    pub fn track_emitted_streams(self, track_emitted_streams: bool) -> Self {
+        Self {
+            track_emitted_streams,
+            ..self
+        }
+    }
+
+    pub fn enabled(self, enabled: bool) -> Self {
+        Self { enabled, ..self }
+    }
+}
+
+/// A single step of a [`SyncPlan`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ProjectionChange {
+    Create(DesiredProjection),
+    Update {
+        name: String,
+        query: String,
+        emit: bool,
+    },
+    Enable(String),
+    Disable(String),
+    Delete(String),
+}
+
+impl Display for ProjectionChange {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            ProjectionChange::Create(p) => write!(
+                f,
+                "+ create {} (emit={}, track_emitted_streams={}, enabled={})",
+                p.name, p.emit, p.track_emitted_streams, p.enabled
+            ),
+            ProjectionChange::Update { name, emit, .. } => {
+                write!(f, "~ update {} (emit={})", name, emit)
+            }
+            ProjectionChange::Enable(name) => write!(f, "~ enable {}", name),
+            ProjectionChange::Disable(name) => write!(f, "~ disable {}", name),
+            ProjectionChange::Delete(name) => write!(f, "- delete {}", name),
+        }
+    }
+}
+
+/// Changes that make the server projections match the desired ones, in execution order.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct SyncPlan {
+    pub changes: Vec<ProjectionChange>,
+}
+
+impl SyncPlan {
+    /// The server already matches the desired projections.
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty()
+    }
+}
+
+impl Display for SyncPlan {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        if self.changes.is_empty() {
+            return writeln!(f, "projections are up to date");
+        }
+
+        for change in self.changes.iter() {
+            writeln!(f, "{}", change)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Compares the desired projections with the continuous projections of the server. When `prune`
+/// is set, projections missing from `desired` are deleted.
+pub async fn plan(
+    client: &ProjectionClient,
+    desired: &[DesiredProjection],
+    prune: bool,
+) -> kurrentdb::Result<SyncPlan> {
+    let options = GenericProjectionOptions::default();
+    let mut names = Vec::new();
+    let mut statuses = client.list(&options).await?;
+
+    while let Some(status) = statuses.try_next().await? {
+        if !status.name.starts_with('$') {
+            names.push(status.name);
+        }
+    }
+
+    let mut current = Vec::with_capacity(names.len());
+    for name in names {
+        match client.get_definition(name.as_str(), &options).await? {
+            Some(definition) => current.push(definition),
+            None => warn!("Projection '{}' has no stored definition, skipped", name),
+        }
+    }
+
+    Ok(diff(desired, &current, prune))
+}
+
+/// Computes the changes turning `current` into `desired`.
+pub fn diff(
+    desired: &[DesiredProjection],
+    current: &[ProjectionDefinition],
+    prune: bool,
+) -> SyncPlan {
+    let mut changes = Vec::new();
+
+    for wanted in desired {
+        let existing = match current.iter().find(|c| c.name == wanted.name) {
+            None => {
+                changes.push(ProjectionChange::Create(wanted.clone()));
+                continue;
+            }
+
+            Some(existing) => existing,
+        };
+
+        if existing.query.trim() != wanted.query.trim() || existing.emit_enabled != wanted.emit {
+            changes.push(ProjectionChange::Update {
+                name: wanted.name.clone(),
+                query: wanted.query.clone(),
+                emit: wanted.emit,
+            });
+        }
+
+        if existing.track_emitted_streams != wanted.track_emitted_streams {
+            warn!(
+                "Projection '{}' can't change track_emitted_streams once created",
+                wanted.name
+            );
+        }
+
+        if existing.enabled != wanted.enabled {
+            let name = wanted.name.clone();
+
+            changes.push(if wanted.enabled {
+                ProjectionChange::Enable(name)
+            } else {
+                ProjectionChange::Disable(name)
+            });
+        }
+    }
+
+    if prune {
+        for existing in current {
+            if !desired.iter().any(|d| d.name == existing.name) {
+                changes.push(ProjectionChange::Delete(existing.name.clone()));
+            }
+        }
+    }
+
+    SyncPlan { changes }
+}
+
+/// Performs the changes of a plan, in order. Stops at the first failing change.
+///
+/// The server can't create a stopped projection, so a projection meant to be disabled is created
+/// running and disabled right after. It may process events in between, but it's created with
+/// `emit` off and only gets its `emit` setting once disabled, so it never emits events.
+pub async fn apply(client: &ProjectionClient, plan: &SyncPlan) -> kurrentdb::Result<()> {
+    let options = GenericProjectionOptions::default();
+
+    for change in plan.changes.iter() {
+        info!("{}", change);
+
+        match change {
+            ProjectionChange::Create(p) => {
+                let create_options = CreateProjectionOptions::default()
+                    .emit(p.emit && p.enabled)
+                    .track_emitted_streams(p.track_emitted_streams);
+
+                client
+                    .create(p.name.as_str(), p.query.clone(), &create_options)
+                    .await?;
+
+                if !p.enabled {
+                    client.disable(p.name.as_str(), &options).await?;
+
+                    if p.emit {
+                        let update_options = UpdateProjectionOptions::default().emit(true);
+
+                        client
+                            .update(p.name.as_str(), p.query.clone(), &update_options)
+                            .await?;
+                    }
+                }
+            }
+
+            ProjectionChange::Update { name, query, emit } => {
+                let update_options = UpdateProjectionOptions::default().emit(*emit);
+
+                client
+                    .update(name.as_str(), query.clone(), &update_options)
+                    .await?;
+            }
+
+            ProjectionChange::Enable(name) => client.enable(name.as_str(), &options).await?,
+
+            ProjectionChange::Disable(name) => client.disable(name.as_str(), &options).await?,
+
+            ProjectionChange::Delete(name) => {
+                // The server only deletes stopped projections.
+                client.disable(name.as_str(), &options).await?;
+                client
+                    .delete(name.as_str(), &DeleteProjectionOptions::default())
+                    .await?;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Loads every `.js` file of a directory as a projection named after the file. Settings are read
+/// from an optional line of the comments opening the file, e.g.
+/// `// kurrentdb: emit=true, track_emitted_streams=true, enabled=false`.
+pub fn load_dir(path: impl AsRef<Path>) -> std::io::Result<Vec<DesiredProjection>> {
+    let mut projections = Vec::new();
+
+    for entry in std::fs::read_dir(path)? {
+        let path = entry?.path();
+
+        if path.extension().is_none_or(|ext| ext != "js") {
+            continue;
+        }
+
+        let Some(name) = path.file_stem().and_then(|s| s.to_str()) else {
+            continue;
+        };
+
+        let query = std::fs::read_to_string(&path)?;
+        projections.push(parse_projection(name, query)?);
+    }
+
+    projections.sort_by(|a, b| a.name.cmp(&b.name));
+
+    Ok(projections)
+}
+
+fn parse_projection(name: &str, query: String) -> std::io::Result<DesiredProjection> {
+    let invalid = |message: String| std::io::Error::new(std::io::ErrorKind::InvalidData, message);
+    let mut projection = DesiredProjection::new(name, String::new());
+
+    // Past the leading comments, the same text belongs to the query, e.g. in a string literal.
+    let directive = query
+        .lines()
+        .map(str::trim)
+        .take_while(|line| line.is_empty() || line.starts_with("//"))
+        .find_map(|line| line.strip_prefix(DIRECTIVE_PREFIX));
+
+    for setting in directive.into_iter().flat_map(|d| d.split(',')) {
+        let setting = setting.trim();
+        if setting.is_empty() {
+            continue;
+        }
+
+        let (key, value) = setting
+            .split_once('=')
+            .ok_or_else(|| invalid(format!("{}: malformed setting '{}'", name, setting)))?;
+
+        let value = value
+            .trim()
+            .parse::<bool>()
+            .map_err(|e| invalid(format!("{}: {} for '{}'", name, e, key.trim())))?;
+
+        match key.trim() {
+            "emit" => projection.emit = value,
+            "track_emitted_streams" => projection.track_emitted_streams = value,
+            "enabled" => projection.enabled = value,
+            unknown => return Err(invalid(format!("{}: unknown setting '{}'", name, unknown))),
+        }
+    }
+
+    projection.query = query;
+
+    Ok(projection)
+}
+
+#[cfg(test)]
+mod projections_tests {
+    use super::*;
+    use kurrentdb::ProjectionMode;
+
+    fn definition(name: &str, query: &str, enabled: bool) -> ProjectionDefinition {
+        ProjectionDefinition {
+            name: name.to_string(),
+            query: query.to_string(),
+            mode: ProjectionMode::Continuous,
+            enabled,
+            emit_enabled: false,
+            track_emitted_streams: false,
+        }
+    }
+
+    #[test]
+    fn test_diff() {
+        let desired = vec![
+            DesiredProjection::new("new", "fromAll()"),
+            DesiredProjection::new("changed", "fromAll().when({})").emit(true),
+            DesiredProjection::new("same", "fromAll()\n"),
+            DesiredProjection::new("stopped", "fromAll()").enabled(false),
+        ];
+
+        let current = vec![
+            definition("changed", "fromAll()", true),
+            definition("same", "fromAll()", true),
+            definition("stopped", "fromAll()", true),
+            definition("stale", "fromAll()", false),
+        ];
+
+        let plan = diff(&desired, &current, false);
+        assert_eq!(
+            plan.changes,
+            vec![
+                ProjectionChange::Create(desired[0].clone()),
+                ProjectionChange::Update {
+                    name: "changed".to_string(),
+                    query: "fromAll().when({})".to_string(),
+                    emit: true,
+                },
+                ProjectionChange::Disable("stopped".to_string()),
+            ]
+        );
+
+        let plan = diff(&desired, &current, true);
+        assert_eq!(
+            plan.changes.last(),
+            Some(&ProjectionChange::Delete("stale".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_parse_projection_settings() {
+        let query = "// kurrentdb: emit=true, enabled=false\nfromAll()".to_string();
+        let projection = parse_projection("counter", query.clone()).unwrap();
+
+        assert_eq!(
+            projection,
+            DesiredProjection::new("counter", query)
+                .emit(true)
+                .enabled(false)
+        );
+
+        assert!(parse_projection("broken", "// kurrentdb: emit=yes".to_string()).is_err());
+
+        let query = "// Counts events.\n\n// kurrentdb: emit=true\nfromAll()".to_string();
+        assert!(parse_projection("counter", query).unwrap().emit);
+
+        let query = "fromAll()\n// kurrentdb: emit=true".to_string();
+        assert_eq!(
+            parse_projection("counter", query.clone()).unwrap(),
+            DesiredProjection::new("counter", query)
+        );
+    }
+}
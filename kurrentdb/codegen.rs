@@ -11,6 +11,8 @@ pub fn generate() -> Result<(), Box<dyn std::error::Error>> {
         .build_server(false)
         .extern_path(".event_store.client.Empty", "()")
         .bytes(&["StreamIdentifier.stream_name"])
+        // Carries nothing beyond the status code, so it's never decoded.
+        .message_attribute(".event_store.client.Unknown", "#[allow(dead_code)]")
         .out_dir(out_dir)
         .compile_protos(&files, &[""])?;
 
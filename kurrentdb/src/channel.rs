@@ -0,0 +1,182 @@
+use std::pin::Pin;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::task::{Context, Poll};
+
+use bytes::Bytes;
+use futures::future::BoxFuture;
+use hyper::body::Incoming;
+use hyper_rustls::HttpsConnector;
+use hyper_util::client::legacy::connect::HttpConnector;
+use tonic::body::BoxBody;
+
+use crate::options::OperationKind;
+
+type RawClient = hyper_util::client::legacy::Client<HttpsConnector<HttpConnector>, BoxBody>;
+
+/// HTTP/2 client that keeps track of how many calls are in flight on its connection. A call is
+/// in flight until its response body is dropped, which for streaming calls means until the stream
+/// ends.
+#[derive(Clone, Debug)]
+pub(crate) struct Channel {
+    client: RawClient,
+    in_flight: Arc<AtomicUsize>,
+}
+
+impl Channel {
+    pub(crate) fn new(client: RawClient) -> Self {
+        Self {
+            client,
+            in_flight: Default::default(),
+        }
+    }
+
+    pub(crate) fn in_flight(&self) -> usize {
+        self.in_flight.load(Ordering::Relaxed)
+    }
+}
+
+impl tower::Service<http::Request<BoxBody>> for Channel {
+    type Response = http::Response<TrackedBody>;
+    type Error = hyper_util::client::legacy::Error;
+    type Future = BoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, req: http::Request<BoxBody>) -> Self::Future {
+        let guard = InFlight::new(self.in_flight.clone());
+        let resp = self.client.request(req);
+
+        Box::pin(async move {
+            let resp = resp.await?;
+
+            Ok(resp.map(|inner| TrackedBody {
+                inner,
+                _guard: guard,
+            }))
+        })
+    }
+}
+
+struct InFlight(Arc<AtomicUsize>);
+
+impl InFlight {
+    fn new(counter: Arc<AtomicUsize>) -> Self {
+        counter.fetch_add(1, Ordering::Relaxed);
+
+        Self(counter)
+    }
+}
+
+impl Drop for InFlight {
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, Ordering::Relaxed);
+    }
+}
+
+/// Response body holding its call in-flight slot until dropped.
+pub(crate) struct TrackedBody {
+    inner: Incoming,
+    _guard: InFlight,
+}
+
+impl http_body::Body for TrackedBody {
+    type Data = Bytes;
+    type Error = hyper::Error;
+
+    fn poll_frame(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<Result<http_body::Frame<Self::Data>, Self::Error>>> {
+        Pin::new(&mut self.inner).poll_frame(cx)
+    }
+
+    fn is_end_stream(&self) -> bool {
+        self.inner.is_end_stream()
+    }
+
+    fn size_hint(&self) -> http_body::SizeHint {
+        self.inner.size_hint()
+    }
+}
+
+/// Channels to the selected node. Streaming operations get their own channels, when configured,
+/// so long-lived subscriptions don't compete with unary commands for HTTP/2 streams.
+#[derive(Clone, Debug)]
+pub(crate) struct ChannelPool {
+    unary: Arc<[Channel]>,
+    streaming: Arc<[Channel]>,
+}
+
+impl ChannelPool {
+    pub(crate) fn new(unary: Vec<Channel>, streaming: Vec<Channel>) -> Self {
+        assert!(
+            !unary.is_empty(),
+            "a channel pool needs at least one channel"
+        );
+
+        Self {
+            unary: unary.into(),
+            streaming: streaming.into(),
+        }
+    }
+
+    /// Returns the least loaded channel for the given kind of operation.
+    pub(crate) fn pick(&self, kind: OperationKind) -> Channel {
+        let channels = if kind == OperationKind::Streaming && !self.streaming.is_empty() {
+            &self.streaming
+        } else {
+            &self.unary
+        };
+
+        channels
+            .iter()
+            .min_by_key(|channel| channel.in_flight())
+            .cloned()
+            .unwrap()
+    }
+}
+
+#[cfg(test)]
+mod channel_pool_tests {
+    use super::*;
+    use crate::ClientSettings;
+
+    fn channel() -> Channel {
+        crate::grpc::create_hyper_client(&ClientSettings::default(), None).unwrap()
+    }
+
+    fn same(a: &Channel, b: &Channel) -> bool {
+        Arc::ptr_eq(&a.in_flight, &b.in_flight)
+    }
+
+    #[tokio::test]
+    async fn test_pick_least_loaded_channel() {
+        let unary = vec![channel(), channel()];
+        let pool = ChannelPool::new(unary.clone(), vec![]);
+        let _busy = InFlight::new(unary[0].in_flight.clone());
+
+        assert!(same(&pool.pick(OperationKind::Regular), &unary[1]));
+
+        let _busy = InFlight::new(unary[1].in_flight.clone());
+        let _busy = InFlight::new(unary[1].in_flight.clone());
+
+        assert!(same(&pool.pick(OperationKind::Regular), &unary[0]));
+    }
+
+    #[tokio::test]
+    async fn test_pick_streaming_channel() {
+        let unary = vec![channel()];
+        let streaming = vec![channel()];
+        let pool = ChannelPool::new(unary.clone(), streaming.clone());
+
+        assert!(same(&pool.pick(OperationKind::Streaming), &streaming[0]));
+        assert!(same(&pool.pick(OperationKind::Regular), &unary[0]));
+
+        let pool = ChannelPool::new(unary.clone(), vec![]);
+
+        assert!(same(&pool.pick(OperationKind::Streaming), &unary[0]));
+    }
+}
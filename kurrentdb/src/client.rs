@@ -6,20 +6,29 @@ use crate::options::read_all::ReadAllOptions;
 use crate::options::read_stream::ReadStreamOptions;
 use crate::options::subscribe_to_stream::SubscribeToStreamOptions;
 use crate::server_features::ServerInfo;
+use crate::types::{MetadataUpdate, ReadDirection};
 use crate::{
     DeletePersistentSubscriptionOptions, DeleteStreamOptions, GetPersistentSubscriptionInfoOptions,
     ListPersistentSubscriptionsOptions, MetadataStreamName, PersistentSubscription,
     PersistentSubscriptionInfo, PersistentSubscriptionToAllOptions, Position, ReadStream,
     ReplayParkedMessagesOptions, RestartPersistentSubscriptionSubsystem, RevisionOrPosition,
     StreamMetadata, StreamMetadataResult, StreamName, SubscribeToAllOptions,
-    SubscribeToPersistentSubscriptionOptions, Subscription, TombstoneStreamOptions,
+    SubscribeToPersistentSubscriptionOptions, Subscription, TlsIdentity, TombstoneStreamOptions,
     VersionedMetadata, WriteResult, commands,
 };
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+
 use crate::{
-    EventData,
+    EventData, GetStreamStatusOptions, GetSystemSettingsOptions, MultiStreamSubscription, Page,
+    PageCursor, ReadEventOptions, RecordedEvent, ResolvedEvent, SetSystemSettingsOptions, StreamId,
+    StreamPosition, StreamState, StreamStatus, SubscribeToStreamsOptions, SystemSettings,
+    UpdateStreamMetadataOptions, UpdateSystemSettingsOptions, VersionedSystemSettings,
     options::append_to_stream::{AppendToStreamOptions, ToEvents},
 };
 
+const SETTINGS_STREAM: &str = "$settings";
+
 /// Represents a client to a single node. `Client` maintains a full duplex
 /// communication to KurrentDB.
 ///
@@ -42,13 +51,17 @@ impl Client {
         handle: tokio::runtime::Handle,
         settings: ClientSettings,
     ) -> crate::Result<Self> {
+        #[cfg(not(feature = "compression"))]
+        if let Some(algorithm) = settings.grpc_compression() {
+            return Err(crate::Error::InitializationError(format!(
+                "gRPC compression '{}' requires the `compression` feature",
+                algorithm
+            )));
+        }
+
         let client = GrpcClient::create(handle, settings.clone());
 
-        let http_client = reqwest::Client::builder()
-            .danger_accept_invalid_certs(!settings.is_tls_certificate_verification_enabled())
-            .https_only(settings.is_secure_mode_enabled())
-            .build()
-            .map_err(|e| crate::Error::InitializationError(e.to_string()))?;
+        let http_client = crate::http::create_http_client(&settings)?;
 
         Ok(Client {
             http_client,
@@ -60,6 +73,22 @@ impl Client {
         self.client.connection_settings()
     }
 
+    /// Registers a TLS client certificate under the given name. Operations performed with the
+    /// `tls_identity` option set to that name use it to authenticate, on their own connections.
+    /// Registering an identity under an existing name replaces it.
+    pub fn add_tls_identity(
+        &self,
+        name: impl Into<String>,
+        identity: &TlsIdentity,
+    ) -> crate::Result<()> {
+        self.client.add_tls_identity(name.into(), identity)
+    }
+
+    /// Unregisters a TLS client certificate. Returns `false` if no identity had that name.
+    pub fn remove_tls_identity(&self, name: impl AsRef<str>) -> bool {
+        self.client.remove_tls_identity(name.as_ref())
+    }
+
     /// Returns the server information the client is connected to. If `None`, means you are dealing
     /// with a server older than 21.6 version.
     pub async fn server_info(&self) -> crate::Result<ServerInfo> {
@@ -81,15 +110,39 @@ impl Client {
         commands::append_to_stream(&self.client, stream_name, options, events.into_events()).await
     }
 
+    /// Appends link events pointing to the given events, typically to build an index stream
+    /// from a subscription.
+    pub async fn append_links<'a, I>(
+        &self,
+        stream_name: impl StreamName,
+        options: &AppendToStreamOptions,
+        events: I,
+    ) -> crate::Result<WriteResult>
+    where
+        I: IntoIterator<Item = &'a RecordedEvent>,
+    {
+        let links = events
+            .into_iter()
+            .map(EventData::link_to)
+            .collect::<Vec<_>>();
+
+        self.append_to_stream(stream_name, options, links).await
+    }
+
     // Sets a stream metadata.
-    pub async fn set_stream_metadata(
+    pub async fn set_stream_metadata<C>(
         &self,
         name: impl MetadataStreamName,
         options: &AppendToStreamOptions,
-        metadata: &StreamMetadata,
-    ) -> crate::Result<WriteResult> {
+        metadata: &StreamMetadata<C>,
+    ) -> crate::Result<WriteResult>
+    where
+        C: Serialize,
+    {
+        // An explicit id makes the write idempotent, so it can be retried on transient errors.
         let event = EventData::json("$metadata", metadata)
-            .map_err(|e| crate::Error::InternalParsingError(e.to_string()))?;
+            .map_err(|e| crate::Error::InternalParsingError(e.to_string()))?
+            .id(uuid::Uuid::new_v4());
 
         self.append_to_stream(name.into_metadata_stream_name(), options, event)
             .await
@@ -119,18 +172,165 @@ impl Client {
         .await
     }
 
+    /// Reads the events of every stream of a category, through the `$ce-` stream of the
+    /// `$by_category` system projection. Links are always resolved.
+    pub async fn read_category(
+        &self,
+        category: impl AsRef<str>,
+        options: &ReadStreamOptions,
+    ) -> crate::Result<ReadStream> {
+        let options = options.clone().resolve_link_tos();
+
+        self.read_stream(StreamId::category_stream(category), &options)
+            .await
+    }
+
     /// Reads events for the system stream `$all`. The reading can be done
     /// forward and backward.
     pub async fn read_all(&self, options: &ReadAllOptions) -> crate::Result<ReadStream> {
         commands::read_all(self.client.clone(), options, options.max_count as u64).await
     }
 
+    /// Reads the event at the given revision of a stream. Returns `None` if the stream or that
+    /// event doesn't exist.
+    pub async fn read_event(
+        &self,
+        stream_name: impl StreamName,
+        revision: u64,
+        options: &ReadEventOptions,
+    ) -> crate::Result<Option<ResolvedEvent>> {
+        let event = self
+            .read_one_backwards(stream_name, StreamPosition::Position(revision), options)
+            .await?;
+
+        // A backward read starting past the end of the stream returns its last event instead.
+        Ok(event.filter(|event| event.get_original_event().revision == revision))
+    }
+
+    /// Reads the last event of a stream. Returns `None` if the stream doesn't exist.
+    pub async fn read_last_event(
+        &self,
+        stream_name: impl StreamName,
+        options: &ReadEventOptions,
+    ) -> crate::Result<Option<ResolvedEvent>> {
+        self.read_one_backwards(stream_name, StreamPosition::End, options)
+            .await
+    }
+
+    /// Tells if a stream exists, was deleted, and what its current revision is.
+    pub async fn get_stream_status(
+        &self,
+        stream_name: impl StreamName,
+        options: &GetStreamStatusOptions,
+    ) -> crate::Result<StreamStatus> {
+        let options = ReadEventOptions {
+            resolve_link_tos: false,
+            common_operation_options: options.common_operation_options.clone(),
+        };
+
+        match self.read_last_event(stream_name, &options).await {
+            Ok(Some(event)) => Ok(StreamStatus::Exists(event.get_original_event().revision)),
+            Ok(None) => Ok(StreamStatus::NotFound),
+            Err(crate::Error::StreamDeleted { .. }) => Ok(StreamStatus::Deleted),
+            Err(e) => Err(e),
+        }
+    }
+
+    async fn read_one_backwards(
+        &self,
+        stream_name: impl StreamName,
+        position: StreamPosition<u64>,
+        options: &ReadEventOptions,
+    ) -> crate::Result<Option<ResolvedEvent>> {
+        let mut read_options = ReadStreamOptions {
+            common_operation_options: options.common_operation_options.clone(),
+            ..ReadStreamOptions::default()
+                .position(position)
+                .backwards()
+                .max_count(1)
+        };
+
+        if options.resolve_link_tos {
+            read_options = read_options.resolve_link_tos();
+        }
+
+        let mut stream = self.read_stream(stream_name, &read_options).await?;
+
+        match stream.next().await {
+            Err(crate::Error::ResourceNotFound) => Ok(None),
+            other => other,
+        }
+    }
+
+    /// Reads a page of at most `page_size` events from a given stream, in the direction of the
+    /// options. Without a cursor, the read starts at the options position, otherwise it resumes
+    /// from where the previous page ended.
+    pub async fn read_stream_page(
+        &self,
+        stream_name: impl StreamName,
+        options: &ReadStreamOptions,
+        cursor: Option<&PageCursor>,
+        page_size: usize,
+    ) -> crate::Result<Page> {
+        let page_size = page_size.max(1);
+        let mut options = options.clone().max_count(page_size.saturating_add(1));
+
+        if let Some(cursor) = cursor {
+            options = options.position(StreamPosition::Position(cursor.stream_revision()?));
+        }
+
+        let stream = self.read_stream(stream_name, &options).await?;
+
+        read_page(stream, page_size, false, |event| {
+            PageCursor::from_stream_revision(event.get_original_event().revision)
+        })
+        .await
+    }
+
+    /// Like [`read_stream_page`] but specific to system `$all` stream.
+    ///
+    /// [`read_stream_page`]: #method.read_stream_page
+    pub async fn read_all_page(
+        &self,
+        options: &ReadAllOptions,
+        cursor: Option<&PageCursor>,
+        page_size: usize,
+    ) -> crate::Result<Page> {
+        let page_size = page_size.max(1);
+        let mut options = options.clone().max_count(page_size.saturating_add(1));
+        // Unlike the other reads, backward `$all` reads exclude the event at their start position.
+        let exclusive_start = matches!(options.direction, ReadDirection::Backward);
+
+        if let Some(cursor) = cursor {
+            options = options.position(StreamPosition::Position(cursor.position()?));
+        }
+
+        let stream = self.read_all(&options).await?;
+
+        read_page(stream, page_size, exclusive_start, |event| {
+            PageCursor::from_position(event.get_original_event().position)
+        })
+        .await
+    }
+
     /// Reads a stream metadata.
     pub async fn get_stream_metadata(
         &self,
         name: impl MetadataStreamName,
         options: &ReadStreamOptions,
     ) -> crate::Result<StreamMetadataResult> {
+        self.get_stream_metadata_as(name, options).await
+    }
+
+    /// Reads a stream metadata, decoding its custom properties as `C`.
+    pub async fn get_stream_metadata_as<C>(
+        &self,
+        name: impl MetadataStreamName,
+        options: &ReadStreamOptions,
+    ) -> crate::Result<StreamMetadataResult<C>>
+    where
+        C: DeserializeOwned,
+    {
         let mut stream = self
             .read_stream(name.into_metadata_stream_name(), options)
             .await?;
@@ -140,7 +340,7 @@ impl Client {
                 let event = event.expect("to be defined");
                 let metadata = event
                     .get_original_event()
-                    .as_json::<StreamMetadata>()
+                    .as_json::<StreamMetadata<C>>()
                     .map_err(|e| crate::Error::InternalParsingError(e.to_string()))?;
 
                 let metadata = VersionedMetadata {
@@ -153,12 +353,187 @@ impl Client {
             }
             Err(e) => match e {
                 crate::Error::ResourceNotFound => Ok(StreamMetadataResult::NotFound),
-                crate::Error::ResourceDeleted => Ok(StreamMetadataResult::Deleted),
+                crate::Error::StreamDeleted { .. } => Ok(StreamMetadataResult::Deleted),
                 other => Err(other),
             },
         }
     }
 
+    /// Reads the latest metadata of a stream, applies the given update and writes the result back,
+    /// expecting the metadata not to have changed in the meantime. On conflict, the update is
+    /// applied again to the fresh metadata. A stream without metadata starts from
+    /// `StreamMetadata::default()`.
+    ///
+    /// The type of custom properties is taken from the update closure argument, for example
+    /// `|metadata: &mut StreamMetadata| ...` for untyped ones. Properties that type doesn't model
+    /// are written back unchanged.
+    pub async fn update_stream_metadata<C, F>(
+        &self,
+        name: impl MetadataStreamName,
+        options: &UpdateStreamMetadataOptions,
+        mut update: F,
+    ) -> crate::Result<WriteResult>
+    where
+        C: Serialize + DeserializeOwned + Default,
+        F: FnMut(&mut StreamMetadata<C>),
+    {
+        let stream = name.into_metadata_stream_name();
+        let read_options = ReadStreamOptions {
+            common_operation_options: options.common_operation_options.clone(),
+            ..ReadStreamOptions::default()
+                .position(StreamPosition::End)
+                .max_count(1)
+        };
+        let mut attempt = 1;
+
+        loop {
+            let (mut metadata, stream_state) = match self
+                .get_stream_metadata(stream.clone(), &read_options)
+                .await?
+            {
+                StreamMetadataResult::Success(versioned) => (
+                    MetadataUpdate::<C>::from_untyped(&versioned.metadata)
+                        .map_err(|e| crate::Error::InternalParsingError(e.to_string()))?,
+                    StreamState::StreamRevision(versioned.version),
+                ),
+                StreamMetadataResult::NotFound => (
+                    MetadataUpdate::new(StreamMetadata::default()),
+                    StreamState::NoStream,
+                ),
+                StreamMetadataResult::Deleted => {
+                    return Err(crate::Error::StreamDeleted {
+                        stream: String::from_utf8_lossy(&stream).into_owned(),
+                        source: None,
+                    });
+                }
+            };
+
+            update(&mut metadata.metadata);
+
+            let metadata = metadata
+                .into_untyped()
+                .map_err(|e| crate::Error::InternalParsingError(e.to_string()))?;
+
+            let append_options = AppendToStreamOptions {
+                common_operation_options: options.common_operation_options.clone(),
+                ..AppendToStreamOptions::default().stream_state(stream_state)
+            };
+
+            match self
+                .set_stream_metadata(stream.clone(), &append_options, &metadata)
+                .await
+            {
+                Err(crate::Error::WrongExpectedVersion { .. })
+                    if attempt < options.max_attempts =>
+                {
+                    attempt += 1;
+                }
+
+                result => return result,
+            }
+        }
+    }
+
+    /// Reads the latest server-wide settings from the `$settings` stream. Returns `None` if they
+    /// were never set.
+    pub async fn get_system_settings(
+        &self,
+        options: &GetSystemSettingsOptions,
+    ) -> crate::Result<Option<VersionedSystemSettings>> {
+        let read_options = ReadStreamOptions {
+            common_operation_options: options.common_operation_options.clone(),
+            ..ReadStreamOptions::default()
+                .position(StreamPosition::End)
+                .max_count(1)
+        };
+
+        let mut stream = match self.read_stream(SETTINGS_STREAM, &read_options).await {
+            Err(crate::Error::ResourceNotFound) => return Ok(None),
+            other => other?,
+        };
+
+        match stream.next().await {
+            Ok(Some(event)) => {
+                let event = event.get_original_event();
+                let settings = event
+                    .as_json::<SystemSettings>()
+                    .map_err(|e| crate::Error::InternalParsingError(e.to_string()))?;
+
+                Ok(Some(VersionedSystemSettings {
+                    version: event.revision,
+                    settings,
+                }))
+            }
+
+            Ok(None) | Err(crate::Error::ResourceNotFound) => Ok(None),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Writes server-wide settings to the `$settings` stream.
+    pub async fn set_system_settings(
+        &self,
+        options: &SetSystemSettingsOptions,
+        settings: &SystemSettings,
+    ) -> crate::Result<WriteResult> {
+        // An explicit id makes the write idempotent, so it can be retried on transient errors.
+        let event = EventData::json("$settings", settings)
+            .map_err(|e| crate::Error::InternalParsingError(e.to_string()))?
+            .id(uuid::Uuid::new_v4());
+
+        let append_options = AppendToStreamOptions {
+            common_operation_options: options.common_operation_options.clone(),
+            ..AppendToStreamOptions::default().stream_state(options.stream_state)
+        };
+
+        self.append_to_stream(SETTINGS_STREAM, &append_options, event)
+            .await
+    }
+
+    /// Reads the latest server-wide settings, applies the given update and writes the result back,
+    /// expecting the settings not to have changed in the meantime. On conflict, the update is
+    /// applied again to the fresh settings.
+    pub async fn update_system_settings<F>(
+        &self,
+        options: &UpdateSystemSettingsOptions,
+        mut update: F,
+    ) -> crate::Result<WriteResult>
+    where
+        F: FnMut(&mut SystemSettings),
+    {
+        let get_options = GetSystemSettingsOptions {
+            common_operation_options: options.common_operation_options.clone(),
+        };
+        let mut attempt = 1;
+
+        loop {
+            let (mut settings, stream_state) = match self.get_system_settings(&get_options).await? {
+                Some(versioned) => (
+                    versioned.settings,
+                    StreamState::StreamRevision(versioned.version),
+                ),
+                None => (SystemSettings::default(), StreamState::NoStream),
+            };
+
+            update(&mut settings);
+
+            let set_options = SetSystemSettingsOptions {
+                common_operation_options: options.common_operation_options.clone(),
+                ..SetSystemSettingsOptions::default().stream_state(stream_state)
+            };
+
+            match self.set_system_settings(&set_options, &settings).await {
+                Err(crate::Error::WrongExpectedVersion { .. })
+                    if attempt < options.max_attempts =>
+                {
+                    attempt += 1;
+                }
+
+                result => return result,
+            }
+        }
+    }
+
     /// Soft deletes a given stream.
     /// Makes use of Truncate before. When a stream is deleted, its Truncate
     /// before is set to the streams current last event number. When a soft
@@ -215,6 +590,35 @@ impl Client {
         commands::subscribe_to_all(self.client.clone(), options)
     }
 
+    /// Subscribes to the events of every stream of a category, through the `$ce-` stream of the
+    /// `$by_category` system projection. Links are always resolved.
+    pub async fn subscribe_to_category(
+        &self,
+        category: impl AsRef<str>,
+        options: &SubscribeToStreamOptions,
+    ) -> Subscription {
+        let options = options.clone().resolve_link_tos();
+
+        self.subscribe_to_stream(StreamId::category_stream(category), &options)
+            .await
+    }
+
+    /// Subscribes to a set of streams, delivering their events in the order they were written
+    /// across all of them. It relies on a subscription to `$all` filtered on those stream names,
+    /// so the user needs to be allowed to read `$all`. Without any stream, the subscription fails
+    /// with [`crate::Error::InvalidFilter`].
+    pub async fn subscribe_to_streams<I>(
+        &self,
+        streams: I,
+        options: &SubscribeToStreamsOptions,
+    ) -> MultiStreamSubscription
+    where
+        I: IntoIterator,
+        I::Item: AsRef<str>,
+    {
+        commands::subscribe_to_streams(self.client.clone(), streams, options)
+    }
+
     /// Creates a persistent subscription group on a stream.
     ///
     /// Persistent subscriptions are special kind of subscription where the
@@ -454,3 +858,40 @@ impl Client {
         .await
     }
 }
+
+/// Reads one more event than the page size. When there is one, it becomes the start of the next
+/// page rather than part of this one. With an `exclusive_start` read, the next page resumes from
+/// the last event of this one, so it starts with that extra event.
+async fn read_page(
+    mut stream: ReadStream,
+    page_size: usize,
+    exclusive_start: bool,
+    cursor_of: impl Fn(&ResolvedEvent) -> PageCursor,
+) -> crate::Result<Page> {
+    // The page size can be arbitrarily large, like `usize::MAX` to read everything at once.
+    let mut events = Vec::with_capacity(page_size.min(1_024));
+
+    while let Some(event) = stream.next().await? {
+        if events.len() == page_size {
+            let resume_from = if exclusive_start {
+                events.last().unwrap_or(&event)
+            } else {
+                &event
+            };
+
+            return Ok(Page {
+                next_cursor: Some(cursor_of(resume_from)),
+                events,
+                is_end: false,
+            });
+        }
+
+        events.push(event);
+    }
+
+    Ok(Page {
+        events,
+        next_cursor: None,
+        is_end: true,
+    })
+}
@@ -5,6 +5,7 @@ use std::time::{Duration, SystemTime};
 
 use futures::TryStreamExt;
 use nom::AsBytes;
+use serde::de::DeserializeOwned;
 use tokio::sync::mpsc;
 use tonic::{Request, Streaming};
 use tracing::{debug, error, warn};
@@ -30,12 +31,13 @@ use crate::types::{
     StreamPosition, StreamState, SubscriptionEvent, WriteResult,
 };
 use crate::{
-    ClientSettings, DeletePersistentSubscriptionOptions, DeleteStreamOptions,
+    ClientSettings, DeletePersistentSubscriptionOptions, DeleteStreamOptions, FilterWindow,
     GetPersistentSubscriptionInfoOptions, ListPersistentSubscriptionsOptions, NakAction,
     PersistentSubscriptionEvent, PersistentSubscriptionInfo, PersistentSubscriptionToAllOptions,
     ReplayParkedMessagesOptions, RestartPersistentSubscriptionSubsystem, RetryOptions,
-    RevisionOrPosition, StreamName, SubscribeToAllOptions,
-    SubscribeToPersistentSubscriptionOptions, SubscriptionFilter, TombstoneStreamOptions,
+    RevisionOrPosition, StreamName, StreamsCheckpoint, SubscribeToAllOptions,
+    SubscribeToPersistentSubscriptionOptions, SubscribeToStreamsOptions, SubscriptionFilter,
+    TombstoneStreamOptions,
 };
 
 fn convert_event_data_to_batch_proposed_message(
@@ -58,9 +60,9 @@ pub fn filter_into_proto(filter: SubscriptionFilter) -> streams::read_req::optio
     use options::filter_options::{Expression, Filter, Window};
     use streams::read_req::options::{self, FilterOptions};
 
-    let window = match filter.max {
-        Some(max) => Window::Max(max),
-        None => Window::Count(()),
+    let window = match filter.window {
+        FilterWindow::Max(max) => Window::Max(max),
+        FilterWindow::Count => Window::Count(()),
     };
 
     let expr = Expression {
@@ -68,6 +70,7 @@ pub fn filter_into_proto(filter: SubscriptionFilter) -> streams::read_req::optio
         prefix: filter.prefixes,
     };
 
+    let checkpoint_interval_multiplier = filter.checkpoint_interval_multiplier;
     let filter = if filter.based_on_stream {
         Filter::StreamIdentifier(expr)
     } else {
@@ -77,7 +80,7 @@ pub fn filter_into_proto(filter: SubscriptionFilter) -> streams::read_req::optio
     FilterOptions {
         filter: Some(filter),
         window: Some(window),
-        checkpoint_interval_multiplier: 1,
+        checkpoint_interval_multiplier,
     }
 }
 
@@ -87,9 +90,9 @@ pub fn ps_create_filter_into_proto(
     use persistent::create_req::all_options::FilterOptions;
     use persistent::create_req::all_options::filter_options::{Expression, Filter, Window};
 
-    let window = match filter.max {
-        Some(max) => Window::Max(max),
-        None => Window::Count(()),
+    let window = match filter.window {
+        FilterWindow::Max(max) => Window::Max(max),
+        FilterWindow::Count => Window::Count(()),
     };
 
     let expr = Expression {
@@ -97,6 +100,7 @@ pub fn ps_create_filter_into_proto(
         prefix: filter.prefixes.clone(),
     };
 
+    let checkpoint_interval_multiplier = filter.checkpoint_interval_multiplier;
     let filter = if filter.based_on_stream {
         Filter::StreamIdentifier(expr)
     } else {
@@ -106,7 +110,7 @@ pub fn ps_create_filter_into_proto(
     FilterOptions {
         filter: Some(filter),
         window: Some(window),
-        checkpoint_interval_multiplier: 1,
+        checkpoint_interval_multiplier,
     }
 }
 
@@ -158,32 +162,35 @@ pub async fn append_to_stream(
         content: Some(header),
     };
 
-    let payload = async_stream::stream! {
-        yield header;
+    // Converting events upfront keeps generated ids stable if the command is retried.
+    let mut idempotent = true;
+    let messages = std::iter::once(header)
+        .chain(events.map(|event| {
+            idempotent &= event.id_opt.is_some();
+            event.into()
+        }))
+        .collect::<Vec<AppendReq>>();
 
-        for event in events {
-            yield event.into();
-        }
-    };
+    let action = |handle: Handle| {
+        let payload = futures::stream::iter(messages.clone());
+        let req = new_request(connection.connection_settings(), options, payload);
 
-    let handle = connection.current_selected_node().await?;
-    let handle_id = handle.id();
-    let req = new_request(connection.connection_settings(), options, payload);
-    let mut client = create_streams_client(handle);
-    let resp = match client.append(req).await {
-        Err(e) => {
-            let e = crate::Error::from_grpc(e);
-            handle_error(&connection.sender, handle_id, &e);
+        async move {
+            let mut client = create_streams_client(handle);
+            let resp = client.append(req).await?.into_inner();
 
-            return Err(e);
+            match resp.result.unwrap() {
+                streams::append_resp::Result::Success(success) => Ok(success.into()),
+                streams::append_resp::Result::WrongExpectedVersion(error) => Err(error.into()),
+            }
         }
-
-        Ok(resp) => resp.into_inner(),
     };
 
-    match resp.result.unwrap() {
-        streams::append_resp::Result::Success(success) => Ok(success.into()),
-        streams::append_resp::Result::WrongExpectedVersion(error) => Err(error.into()),
+    // Appending events with explicit ids is idempotent, the server discards duplicates.
+    if idempotent {
+        connection.execute_idempotent(options, action).await
+    } else {
+        connection.execute(options, action).await
     }
 }
 
@@ -204,7 +211,12 @@ pub async fn batch_append(
     };
 
     let connection = connection.clone();
-    let handle = connection.current_selected_node().await?;
+    let handle = connection
+        .select_node(
+            options.kind(),
+            options.common_operation_options.tls_identity.as_deref(),
+        )
+        .await?;
 
     if !handle.supports_feature(Features::BATCH_APPEND) {
         return Err(crate::Error::UnsupportedFeature);
@@ -322,10 +334,20 @@ pub async fn batch_append(
                                 expected_version,
                             ))
                         }
-                        batch_append_resp::Result::Error(code) => {
-                            let message = code.message;
-                            let code = tonic::Code::from(code.code);
-                            let err = crate::Error::Grpc { code, message };
+                        batch_append_resp::Result::Error(status) => {
+                            let err = status
+                                .details
+                                .as_ref()
+                                .and_then(|details| {
+                                    crate::event_store::generated::error_from_rpc_details(
+                                        details, None,
+                                    )
+                                })
+                                .unwrap_or_else(|| crate::Error::Grpc {
+                                    code: tonic::Code::from(status.code),
+                                    message: status.message,
+                                    source: None,
+                                });
 
                             Err(err)
                         }
@@ -388,9 +410,171 @@ pub struct ReadStream {
     sender: tokio::sync::mpsc::UnboundedSender<Msg>,
     channel_id: uuid::Uuid,
     inner: Streaming<crate::event_store::client::streams::ReadResp>,
+    limits: CollectLimits,
+}
+
+/// Bounds how much a [`ReadStream`] keeps in memory when collecting events. Reaching a limit fails
+/// the collect with [`crate::Error::ReadLimitExceeded`]. Unbounded by default.
+#[derive(Debug, Copy, Clone, Default)]
+pub struct CollectLimits {
+    max_events: Option<usize>,
+    max_bytes: Option<usize>,
+}
+
+impl CollectLimits {
+    /// Maximum number of events collected.
+    pub fn max_events(self, max_events: usize) -> Self {
+        Self {
+            max_events: Some(max_events),
+            ..self
+        }
+    }
+
+    /// Maximum size of collected events, counting their payload and custom metadata.
+    pub fn max_bytes(self, max_bytes: usize) -> Self {
+        Self {
+            max_bytes: Some(max_bytes),
+            ..self
+        }
+    }
+}
+
+/// Events collected from a [`ReadStream`], along with the stream positions reported by the server.
+#[derive(Debug)]
+pub struct CollectedEvents<T = ResolvedEvent> {
+    pub events: Vec<T>,
+    pub first_stream_position: Option<u64>,
+    pub last_stream_position: Option<u64>,
+    pub last_all_stream_position: Option<Position>,
+}
+
+impl<T> Default for CollectedEvents<T> {
+    fn default() -> Self {
+        Self {
+            events: Vec::new(),
+            first_stream_position: None,
+            last_stream_position: None,
+            last_all_stream_position: None,
+        }
+    }
+}
+
+fn event_size(event: &ResolvedEvent) -> usize {
+    [event.event.as_ref(), event.link.as_ref()]
+        .into_iter()
+        .flatten()
+        .map(|event| event.data.len() + event.custom_metadata.len())
+        .sum()
 }
 
 impl ReadStream {
+    /// Sets the limits `collect`, `collect_while` and `collect_as` honor.
+    pub fn collect_limits(self, limits: CollectLimits) -> Self {
+        Self { limits, ..self }
+    }
+
+    /// Reads all the remaining events into memory.
+    pub async fn collect(&mut self) -> crate::Result<CollectedEvents> {
+        self.collect_while(|_| true).await
+    }
+
+    /// Reads events into memory until one doesn't match the predicate. That event is discarded.
+    pub async fn collect_while<F>(&mut self, mut predicate: F) -> crate::Result<CollectedEvents>
+    where
+        F: FnMut(&ResolvedEvent) -> bool,
+    {
+        self.collect_map(|event| Ok(predicate(&event).then_some(event)))
+            .await
+    }
+
+    /// Reads all the remaining events into memory, decoding their JSON payload as `T`. When an
+    /// event is a resolved link, the event it points to is decoded.
+    pub async fn collect_as<T>(&mut self) -> crate::Result<CollectedEvents<T>>
+    where
+        T: DeserializeOwned,
+    {
+        self.collect_map(|event| {
+            let recorded = event.event.as_ref().unwrap_or(event.get_original_event());
+
+            recorded
+                .as_json::<T>()
+                .map(Some)
+                .map_err(|e| crate::Error::InternalParsingError(e.to_string()))
+        })
+        .await
+    }
+
+    /// Folds the remaining events into a state, stopping at the first error.
+    pub async fn try_fold<B, E, F>(&mut self, init: B, mut f: F) -> Result<B, E>
+    where
+        F: FnMut(B, ResolvedEvent) -> Result<B, E>,
+        E: From<crate::Error>,
+    {
+        let mut acc = init;
+
+        while let Some(event) = self.next().await? {
+            acc = f(acc, event)?;
+        }
+
+        Ok(acc)
+    }
+
+    /// Collects the mapped events until the mapping returns `None`.
+    async fn collect_map<T, F>(&mut self, mut map: F) -> crate::Result<CollectedEvents<T>>
+    where
+        F: FnMut(ResolvedEvent) -> crate::Result<Option<T>>,
+    {
+        let mut collected = CollectedEvents::default();
+        let mut bytes = 0usize;
+
+        while let Some(event) = self.next_read_event().await? {
+            match event {
+                ReadEvent::Event(event) => {
+                    let size = event_size(&event);
+                    let Some(value) = map(event)? else {
+                        break;
+                    };
+
+                    if let Some(max) = self.limits.max_events
+                        && collected.events.len() >= max
+                    {
+                        return Err(crate::Error::ReadLimitExceeded(format!(
+                            "more than {} events",
+                            max
+                        )));
+                    }
+
+                    bytes += size;
+
+                    if let Some(max) = self.limits.max_bytes
+                        && bytes > max
+                    {
+                        return Err(crate::Error::ReadLimitExceeded(format!(
+                            "more than {} bytes",
+                            max
+                        )));
+                    }
+
+                    collected.events.push(value);
+                }
+
+                ReadEvent::FirstStreamPosition(position) => {
+                    collected.first_stream_position = Some(position);
+                }
+
+                ReadEvent::LastStreamPosition(position) => {
+                    collected.last_stream_position = Some(position);
+                }
+
+                ReadEvent::LastAllStreamPosition(position) => {
+                    collected.last_all_stream_position = Some(position);
+                }
+            }
+        }
+
+        Ok(collected)
+    }
+
     pub async fn next_read_event(&mut self) -> crate::Result<Option<ReadEvent>> {
         loop {
             match self.inner.try_next().await.map_err(crate::Error::from_grpc) {
@@ -493,7 +677,12 @@ pub async fn read_stream(
     };
 
     let req = new_request(connection.connection_settings(), options, req);
-    let handle = connection.current_selected_node().await?;
+    let handle = connection
+        .select_node(
+            options.kind(),
+            options.common_operation_options.tls_identity.as_deref(),
+        )
+        .await?;
     let channel_id = handle.id();
     let mut client = create_streams_client(handle);
 
@@ -509,6 +698,7 @@ pub async fn read_stream(
             sender: connection.sender.clone(),
             channel_id,
             inner: resp.into_inner(),
+            limits: CollectLimits::default(),
         }),
     }
 }
@@ -546,7 +736,10 @@ pub async fn read_all(
     };
 
     let filter_option = match &options.filter {
-        Some(filter) => options::FilterOption::Filter(filter_into_proto(filter.clone())),
+        Some(filter) => {
+            filter.validate()?;
+            options::FilterOption::Filter(filter_into_proto(filter.clone()))
+        }
         None => options::FilterOption::NoFilter(()),
     };
 
@@ -569,7 +762,12 @@ pub async fn read_all(
     };
 
     let req = new_request(connection.connection_settings(), options, req);
-    let handle = connection.current_selected_node().await?;
+    let handle = connection
+        .select_node(
+            options.kind(),
+            options.common_operation_options.tls_identity.as_deref(),
+        )
+        .await?;
     let channel_id = handle.id();
     let mut client = create_streams_client(handle);
 
@@ -585,6 +783,7 @@ pub async fn read_all(
             sender: connection.sender.clone(),
             channel_id,
             inner: resp.into_inner(),
+            limits: CollectLimits::default(),
         }),
     }
 }
@@ -615,34 +814,34 @@ pub async fn delete_stream(
         expected_stream_revision,
     };
 
-    let req = new_request(
-        connection.connection_settings(),
-        options,
-        streams::DeleteReq {
-            options: Some(req_options),
-        },
-    );
+    let req = streams::DeleteReq {
+        options: Some(req_options),
+    };
 
     connection
-        .execute(|handle| async {
-            let mut client = create_streams_client(handle);
-            let result = client.delete(req).await?.into_inner();
+        .execute(options, |handle| {
+            let req = new_request(connection.connection_settings(), options, req.clone());
 
-            if let Some(opts) = result.position_option {
-                match opts {
-                    PositionOption::Position(pos) => {
-                        let pos = Position {
-                            commit: pos.commit_position,
-                            prepare: pos.prepare_position,
-                        };
+            async move {
+                let mut client = create_streams_client(handle);
+                let result = client.delete(req).await?.into_inner();
 
-                        Ok(Some(pos))
-                    }
+                if let Some(opts) = result.position_option {
+                    match opts {
+                        PositionOption::Position(pos) => {
+                            let pos = Position {
+                                commit: pos.commit_position,
+                                prepare: pos.prepare_position,
+                            };
+
+                            Ok(Some(pos))
+                        }
 
-                    PositionOption::NoPosition(_) => Ok(None),
+                        PositionOption::NoPosition(_) => Ok(None),
+                    }
+                } else {
+                    Ok(None)
                 }
-            } else {
-                Ok(None)
             }
         })
         .await
@@ -674,34 +873,34 @@ pub async fn tombstone_stream(
         expected_stream_revision,
     };
 
-    let req = new_request(
-        connection.connection_settings(),
-        options,
-        streams::TombstoneReq {
-            options: Some(req_options),
-        },
-    );
+    let req = streams::TombstoneReq {
+        options: Some(req_options),
+    };
 
     connection
-        .execute(|handle| async {
-            let mut client = create_streams_client(handle);
-            let result = client.tombstone(req).await?.into_inner();
+        .execute(options, |handle| {
+            let req = new_request(connection.connection_settings(), options, req.clone());
+
+            async move {
+                let mut client = create_streams_client(handle);
+                let result = client.tombstone(req).await?.into_inner();
 
-            if let Some(opts) = result.position_option {
-                match opts {
-                    PositionOption::Position(pos) => {
-                        let pos = Position {
-                            commit: pos.commit_position,
-                            prepare: pos.prepare_position,
-                        };
+                if let Some(opts) = result.position_option {
+                    match opts {
+                        PositionOption::Position(pos) => {
+                            let pos = Position {
+                                commit: pos.commit_position,
+                                prepare: pos.prepare_position,
+                            };
 
-                        Ok(Some(pos))
-                    }
+                            Ok(Some(pos))
+                        }
 
-                    PositionOption::NoPosition(_) => Ok(None),
+                        PositionOption::NoPosition(_) => Ok(None),
+                    }
+                } else {
+                    Ok(None)
                 }
-            } else {
-                Ok(None)
             }
         })
         .await
@@ -717,6 +916,9 @@ pub struct Subscription {
     delay: std::time::Duration,
     options: streams::read_req::Options,
     metadata: tonic::metadata::MetadataMap,
+    tls_identity: Option<String>,
+    // Reported on the first read, as creating a subscription can't fail.
+    error: Option<crate::Error>,
 }
 
 impl Subscription {
@@ -725,6 +927,7 @@ impl Subscription {
         retry: Option<RetryOptions>,
         metadata: tonic::metadata::MetadataMap,
         options: streams::read_req::Options,
+        tls_identity: Option<String>,
     ) -> Self {
         let (limit, delay, retry_enabled) = if let Some(retry) = retry {
             (retry.limit, retry.delay, true)
@@ -742,6 +945,8 @@ impl Subscription {
             stream: None,
             attempts: 1,
             metadata,
+            tls_identity,
+            error: None,
         }
     }
 
@@ -758,6 +963,10 @@ impl Subscription {
         use streams::read_req::options::stream_options::RevisionOption;
         use streams::read_req::options::{self, StreamOption};
 
+        if let Some(e) = self.error.take() {
+            return Err(e);
+        }
+
         loop {
             if let Some(mut stream) = self.stream.take() {
                 match stream.try_next().await {
@@ -856,7 +1065,10 @@ impl Subscription {
             } else {
                 debug!("Subscribing...");
                 debug!("Before waiting for the current selected node");
-                let handle = self.connection.current_selected_node().await?;
+                let handle = self
+                    .connection
+                    .select_node(OperationKind::Streaming, self.tls_identity.as_deref())
+                    .await?;
                 debug!("Received selected node");
 
                 self.channel_id = handle.id();
@@ -953,7 +1165,13 @@ pub fn subscribe_to_stream(
         connection.connection_settings(),
         options.common_operation_options(),
     );
-    Subscription::new(connection, retry, metadata, req_options)
+    Subscription::new(
+        connection,
+        retry,
+        metadata,
+        req_options,
+        options.common_operation_options.tls_identity.clone(),
+    )
 }
 
 pub fn subscribe_to_all(connection: GrpcClient, options: &SubscribeToAllOptions) -> Subscription {
@@ -1000,7 +1218,101 @@ pub fn subscribe_to_all(connection: GrpcClient, options: &SubscribeToAllOptions)
         connection.connection_settings(),
         options.common_operation_options(),
     );
-    Subscription::new(connection, retry, metadata, req_options)
+    let mut subscription = Subscription::new(
+        connection,
+        retry,
+        metadata,
+        req_options,
+        options.common_operation_options.tls_identity.clone(),
+    );
+
+    if let Some(filter) = options.filter.as_ref() {
+        subscription.error = filter.validate().err();
+    }
+
+    subscription
+}
+
+/// Subscription to a set of streams, delivering their events in transaction log order.
+pub struct MultiStreamSubscription {
+    inner: Subscription,
+    checkpoint: StreamsCheckpoint,
+}
+
+impl MultiStreamSubscription {
+    pub async fn next(&mut self) -> crate::Result<ResolvedEvent> {
+        loop {
+            if let SubscriptionEvent::EventAppeared(event) = self.next_subscription_event().await? {
+                return Ok(event);
+            }
+        }
+    }
+
+    pub async fn next_subscription_event(&mut self) -> crate::Result<SubscriptionEvent> {
+        let event = self.inner.next_subscription_event().await?;
+
+        match &event {
+            SubscriptionEvent::EventAppeared(event) => {
+                let original = event.get_original_event();
+
+                self.checkpoint.position = Some(original.position);
+                self.checkpoint
+                    .revisions
+                    .insert(original.stream_id().to_string(), original.revision);
+            }
+
+            SubscriptionEvent::Checkpoint(position) => {
+                self.checkpoint.position = Some(*position);
+            }
+
+            _ => {}
+        }
+
+        Ok(event)
+    }
+
+    /// Progress covering every event returned so far.
+    pub fn checkpoint(&self) -> &StreamsCheckpoint {
+        &self.checkpoint
+    }
+}
+
+pub fn subscribe_to_streams<I>(
+    connection: GrpcClient,
+    streams: I,
+    options: &SubscribeToStreamsOptions,
+) -> MultiStreamSubscription
+where
+    I: IntoIterator,
+    I::Item: AsRef<str>,
+{
+    let streams = streams
+        .into_iter()
+        .map(|stream| stream.as_ref().to_string())
+        .collect::<Vec<_>>();
+    let checkpoint = options.checkpoint.clone().unwrap_or_default();
+    let position = match checkpoint.position {
+        Some(position) => StreamPosition::Position(position),
+        None => StreamPosition::Start,
+    };
+
+    let all_options = SubscribeToAllOptions {
+        position,
+        resolve_link_tos: options.resolve_link_tos,
+        filter: Some(SubscriptionFilter::stream_names(&streams)),
+        retry: options.retry,
+        common_operation_options: options.common_operation_options.clone(),
+    };
+
+    let mut inner = subscribe_to_all(connection, &all_options);
+
+    if streams.is_empty() {
+        inner.error = Some(crate::Error::InvalidFilter {
+            reason: "subscribing to streams needs at least one stream".to_string(),
+        });
+    }
+
+    MultiStreamSubscription { inner, checkpoint }
 }
 
 /// This trait is used to avoid code duplication when introducing persistent subscription to $all. It
@@ -1019,6 +1331,10 @@ pub(crate) trait PsSettings: crate::options::Options {
         &self,
         stream_identifier: StreamIdentifier,
     ) -> persistent::update_req::options::StreamOption;
+
+    fn validate(&self) -> crate::Result<()> {
+        Ok(())
+    }
 }
 
 impl PsSettings for PersistentSubscriptionOptions {
@@ -1076,6 +1392,13 @@ impl PsSettings for PersistentSubscriptionToAllOptions {
         &self.setts
     }
 
+    fn validate(&self) -> crate::Result<()> {
+        match self.filter.as_ref() {
+            Some(filter) => filter.validate(),
+            None => Ok(()),
+        }
+    }
+
     fn to_create_options(
         &self,
         _stream_identifier: StreamIdentifier,
@@ -1141,7 +1464,8 @@ where
     use persistent::CreateReq;
     use persistent::create_req::Options;
 
-    let handle = connection.current_selected_node().await?;
+    options.validate()?;
+
     let settings = options.settings().try_into()?;
     let stream_identifier = StreamIdentifier {
         stream_name: stream.into_stream_name(),
@@ -1153,10 +1477,6 @@ where
         persistent::create_req::options::StreamOption::All(_)
     );
 
-    if is_to_all && !handle.supports_feature(Features::PERSISTENT_SUBSCRIPITON_TO_ALL) {
-        return Err(crate::Error::UnsupportedFeature);
-    }
-
     #[allow(deprecated)]
     let req_options = Options {
         stream_option: Some(req_options),
@@ -1169,18 +1489,22 @@ where
         options: Some(req_options),
     };
 
-    let req = new_request(connection.connection_settings(), options, req);
+    connection
+        .execute(options, |handle| {
+            let req = new_request(connection.connection_settings(), options, req.clone());
 
-    let id = handle.id();
-    let mut client = create_persistent_subscriptions_client(handle);
-    if let Err(e) = client.create(req).await {
-        let e = crate::Error::from_grpc(e);
-        handle_error(&connection.sender, id, &e);
+            async move {
+                if is_to_all && !handle.supports_feature(Features::PERSISTENT_SUBSCRIPITON_TO_ALL) {
+                    return Err(crate::Error::UnsupportedFeature);
+                }
 
-        return Err(e);
-    };
+                let mut client = create_persistent_subscriptions_client(handle);
+                client.create(req).await?;
 
-    Ok(())
+                Ok(())
+            }
+        })
+        .await
 }
 
 pub(crate) async fn update_persistent_subscription<S: AsRef<str>, Options>(
@@ -1195,7 +1519,6 @@ where
     use persistent::UpdateReq;
     use persistent::update_req::Options;
 
-    let handle = connection.current_selected_node().await?;
     let settings = options.settings().try_into()?;
     let stream_identifier = StreamIdentifier {
         stream_name: stream.into_stream_name(),
@@ -1207,10 +1530,6 @@ where
         persistent::update_req::options::StreamOption::All(_)
     );
 
-    if is_to_all && !handle.supports_feature(Features::PERSISTENT_SUBSCRIPITON_TO_ALL) {
-        return Err(crate::Error::UnsupportedFeature);
-    }
-
     #[allow(deprecated)]
     let req_options = Options {
         group_name: group.as_ref().to_string(),
@@ -1223,17 +1542,22 @@ where
         options: Some(req_options),
     };
 
-    let req = new_request(connection.connection_settings(), options, req);
-    let id = handle.id();
-    let mut client = create_persistent_subscriptions_client(handle);
-    if let Err(e) = client.update(req).await {
-        let e = crate::Error::from_grpc(e);
-        handle_error(&connection.sender, id, &e);
+    connection
+        .execute_idempotent(options, |handle| {
+            let req = new_request(connection.connection_settings(), options, req.clone());
 
-        return Err(e);
-    }
+            async move {
+                if is_to_all && !handle.supports_feature(Features::PERSISTENT_SUBSCRIPITON_TO_ALL) {
+                    return Err(crate::Error::UnsupportedFeature);
+                }
+
+                let mut client = create_persistent_subscriptions_client(handle);
+                client.update(req).await?;
 
-    Ok(())
+                Ok(())
+            }
+        })
+        .await
 }
 
 pub async fn delete_persistent_subscription<S: AsRef<str>>(
@@ -1245,12 +1569,6 @@ pub async fn delete_persistent_subscription<S: AsRef<str>>(
 ) -> crate::Result<()> {
     use persistent::delete_req::{Options, options::StreamOption};
 
-    let handle = connection.current_selected_node().await?;
-
-    if to_all && !handle.supports_feature(Features::PERSISTENT_SUBSCRIPITON_TO_ALL) {
-        return Err(crate::Error::UnsupportedFeature);
-    };
-
     let stream_option = if !to_all {
         StreamOption::StreamIdentifier(StreamIdentifier {
             stream_name: stream_id.into_stream_name(),
@@ -1270,18 +1588,22 @@ pub async fn delete_persistent_subscription<S: AsRef<str>>(
         options: Some(req_options),
     };
 
-    let req = new_request(connection.connection_settings(), options, req);
-    let id = handle.id();
-    let mut client = create_persistent_subscriptions_client(handle);
+    connection
+        .execute(options, |handle| {
+            let req = new_request(connection.connection_settings(), options, req.clone());
 
-    if let Err(e) = client.delete(req).await {
-        let e = crate::Error::from_grpc(e);
-        handle_error(&connection.sender, id, &e);
+            async move {
+                if to_all && !handle.supports_feature(Features::PERSISTENT_SUBSCRIPITON_TO_ALL) {
+                    return Err(crate::Error::UnsupportedFeature);
+                }
 
-        return Err(e);
-    }
+                let mut client = create_persistent_subscriptions_client(handle);
+                client.delete(req).await?;
 
-    Ok(())
+                Ok(())
+            }
+        })
+        .await
 }
 
 /// Sends the persistent subscription connection request to the server
@@ -1297,7 +1619,12 @@ pub async fn subscribe_to_persistent_subscription<S: AsRef<str>>(
     use persistent::read_req::options::{self, UuidOption};
     use persistent::read_req::{self, Options, options::StreamOption};
 
-    let handle = connection.current_selected_node().await?;
+    let handle = connection
+        .select_node(
+            options.kind(),
+            options.common_operation_options.tls_identity.as_deref(),
+        )
+        .await?;
 
     if to_all && !handle.supports_feature(Features::PERSISTENT_SUBSCRIPITON_TO_ALL) {
         return Err(crate::Error::UnsupportedFeature);
@@ -1483,8 +1810,11 @@ impl PersistentSubscription {
         })
     }
 }
+#[derive(Clone)]
 pub(crate) struct RegularStream(pub(crate) String);
+#[derive(Clone)]
 pub(crate) struct AllStream;
+#[derive(Clone)]
 pub(crate) struct BothTypeOfStream;
 
 pub(crate) trait StreamPositionTypeSelector {
@@ -1555,28 +1885,34 @@ pub async fn list_all_persistent_subscriptions(
 ) -> crate::Result<Vec<PersistentSubscriptionInfo<RevisionOrPosition>>> {
     use crate::event_store::generated::persistent::list_req;
 
-    let handle = connection.current_selected_node().await?;
-
-    if !handle.supports_feature(Features::PERSISTENT_SUBSCRIPTION_MANAGEMENT) {
-        return crate::http::persistent_subscriptions::list_all_persistent_subscriptions(
-            &handle,
-            http_client,
-            connection.connection_settings(),
-            op_options,
-        )
-        .await;
-    }
-
     let list_option = list_req::options::ListOption::ListAllSubscriptions(());
 
-    internal_list_persistent_subscriptions(
-        connection.connection_settings(),
-        handle,
-        op_options,
-        BothTypeOfStream,
-        list_option,
-    )
-    .await
+    connection
+        .execute_idempotent(op_options, |handle| {
+            let list_option = list_option.clone();
+
+            async move {
+                if !handle.supports_feature(Features::PERSISTENT_SUBSCRIPTION_MANAGEMENT) {
+                    return crate::http::persistent_subscriptions::list_all_persistent_subscriptions(
+                        &handle,
+                        http_client,
+                        connection.connection_settings(),
+                        op_options,
+                    )
+                    .await;
+                }
+
+                internal_list_persistent_subscriptions(
+                    connection.connection_settings(),
+                    handle,
+                    op_options,
+                    BothTypeOfStream,
+                    list_option,
+                )
+                .await
+            }
+        })
+        .await
 }
 
 pub(crate) async fn list_persistent_subscriptions_for_stream<StreamName>(
@@ -1586,29 +1922,10 @@ pub(crate) async fn list_persistent_subscriptions_for_stream<StreamName>(
     op_options: &ListPersistentSubscriptionsOptions,
 ) -> crate::Result<Vec<PersistentSubscriptionInfo<<StreamName as StreamPositionTypeSelector>::Value>>>
 where
-    StreamName: StreamKind + StreamPositionTypeSelector,
+    StreamName: StreamKind + StreamPositionTypeSelector + Clone,
 {
     use crate::event_store::generated::persistent::list_req;
 
-    let handle = connection.current_selected_node().await?;
-
-    if !handle.supports_feature(Features::PERSISTENT_SUBSCRIPTION_MANAGEMENT) {
-        if stream_name.is_all()
-            && !handle.supports_feature(Features::PERSISTENT_SUBSCRIPITON_TO_ALL)
-        {
-            return Err(crate::Error::UnsupportedFeature);
-        }
-
-        return crate::http::persistent_subscriptions::list_persistent_subscriptions_for_stream(
-            &handle,
-            http_client,
-            connection.connection_settings(),
-            stream_name,
-            op_options,
-        )
-        .await;
-    }
-
     let stream_option = if stream_name.is_all() {
         list_req::stream_option::StreamOption::All(())
     } else {
@@ -1621,14 +1938,40 @@ where
         stream_option: Some(stream_option),
     });
 
-    internal_list_persistent_subscriptions(
-        connection.connection_settings(),
-        handle,
-        op_options,
-        stream_name,
-        list_option,
-    )
-    .await
+    connection
+        .execute_idempotent(op_options, |handle| {
+            let stream_name = stream_name.clone();
+            let list_option = list_option.clone();
+
+            async move {
+                if !handle.supports_feature(Features::PERSISTENT_SUBSCRIPTION_MANAGEMENT) {
+                    if stream_name.is_all()
+                        && !handle.supports_feature(Features::PERSISTENT_SUBSCRIPITON_TO_ALL)
+                    {
+                        return Err(crate::Error::UnsupportedFeature);
+                    }
+
+                    return crate::http::persistent_subscriptions::list_persistent_subscriptions_for_stream(
+                        &handle,
+                        http_client,
+                        connection.connection_settings(),
+                        stream_name,
+                        op_options,
+                    )
+                    .await;
+                }
+
+                internal_list_persistent_subscriptions(
+                    connection.connection_settings(),
+                    handle,
+                    op_options,
+                    stream_name,
+                    list_option,
+                )
+                .await
+            }
+        })
+        .await
 }
 
 async fn internal_list_persistent_subscriptions<Selector>(
@@ -1651,29 +1994,16 @@ where
     };
 
     let req = new_request(settings, op_options, req);
-    let id = handle.id();
-    let mut client = create_persistent_subscriptions_client(handle.clone());
-
-    match client.list(req).await {
-        Err(status) => {
-            let e = crate::Error::from_grpc(status);
-            handle_error(handle.sender(), id, &e);
-
-            Err(e)
-        }
-
-        Ok(resp) => {
-            let resp = resp.into_inner();
-            let mut infos = Vec::with_capacity(resp.subscriptions.capacity());
-
-            for info in resp.subscriptions {
-                let sub: PersistentSubscriptionInfo<RevisionOrPosition> = info.try_into()?;
-                infos.push(sub.map(|i| selector.select(i)));
-            }
+    let mut client = create_persistent_subscriptions_client(handle);
+    let resp = client.list(req).await?.into_inner();
+    let mut infos = Vec::with_capacity(resp.subscriptions.capacity());
 
-            Ok(infos)
-        }
+    for info in resp.subscriptions {
+        let sub: PersistentSubscriptionInfo<RevisionOrPosition> = info.try_into()?;
+        infos.push(sub.map(|i| selector.select(i)));
     }
+
+    Ok(infos)
 }
 
 pub(crate) async fn replay_parked_messages<StreamName>(
@@ -1688,26 +2018,6 @@ where
 {
     use crate::event_store::generated::persistent::{ReplayParkedReq, replay_parked_req};
 
-    let handle = connection.current_selected_node().await?;
-
-    if !handle.supports_feature(Features::PERSISTENT_SUBSCRIPTION_MANAGEMENT) {
-        if stream_name.is_all()
-            && !handle.supports_feature(Features::PERSISTENT_SUBSCRIPITON_TO_ALL)
-        {
-            return Err(crate::Error::UnsupportedFeature);
-        }
-
-        return crate::http::persistent_subscriptions::replay_parked_messages(
-            &handle,
-            http_client,
-            connection.connection_settings(),
-            stream_name.name(),
-            group_name,
-            op_options,
-        )
-        .await;
-    }
-
     let stream_option = if stream_name.is_all() {
         replay_parked_req::options::StreamOption::All(())
     } else {
@@ -1732,17 +2042,39 @@ where
         options: Some(options),
     };
 
-    let req = new_request(connection.connection_settings(), op_options, req);
-    let id = handle.id();
-    let mut client = create_persistent_subscriptions_client(handle);
-    if let Err(e) = client.replay_parked(req).await {
-        let e = crate::Error::from_grpc(e);
-        handle_error(&connection.sender, id, &e);
+    let stream_name = &stream_name;
+    let group_name = group_name.as_ref();
 
-        return Err(e);
-    }
+    connection
+        .execute(op_options, |handle| {
+            let req = new_request(connection.connection_settings(), op_options, req.clone());
+
+            async move {
+                if !handle.supports_feature(Features::PERSISTENT_SUBSCRIPTION_MANAGEMENT) {
+                    if stream_name.is_all()
+                        && !handle.supports_feature(Features::PERSISTENT_SUBSCRIPITON_TO_ALL)
+                    {
+                        return Err(crate::Error::UnsupportedFeature);
+                    }
+
+                    return crate::http::persistent_subscriptions::replay_parked_messages(
+                        &handle,
+                        http_client,
+                        connection.connection_settings(),
+                        stream_name.name(),
+                        group_name,
+                        op_options,
+                    )
+                    .await;
+                }
 
-    Ok(())
+                let mut client = create_persistent_subscriptions_client(handle);
+                client.replay_parked(req).await?;
+
+                Ok(())
+            }
+        })
+        .await
 }
 
 pub(crate) async fn get_persistent_subscription_info<StreamName>(
@@ -1753,28 +2085,9 @@ pub(crate) async fn get_persistent_subscription_info<StreamName>(
     op_options: &GetPersistentSubscriptionInfoOptions,
 ) -> crate::Result<PersistentSubscriptionInfo<<StreamName as StreamPositionTypeSelector>::Value>>
 where
-    StreamName: StreamKind + StreamPositionTypeSelector,
+    StreamName: StreamKind + StreamPositionTypeSelector + Clone,
 {
     use crate::event_store::generated::persistent::{GetInfoReq, get_info_req};
-    let handle = connection.current_selected_node().await?;
-
-    if !handle.supports_feature(Features::PERSISTENT_SUBSCRIPTION_MANAGEMENT) {
-        if stream_name.is_all()
-            && !handle.supports_feature(Features::PERSISTENT_SUBSCRIPITON_TO_ALL)
-        {
-            return Err(crate::Error::UnsupportedFeature);
-        }
-
-        return crate::http::persistent_subscriptions::get_persistent_subscription_info(
-            &handle,
-            http_client,
-            connection.connection_settings(),
-            stream_name,
-            group_name,
-            op_options,
-        )
-        .await;
-    }
 
     let stream_option = if stream_name.is_all() {
         get_info_req::options::StreamOption::All(())
@@ -1790,30 +2103,45 @@ where
     let req = GetInfoReq {
         options: Some(options),
     };
-    let req = new_request(connection.connection_settings(), op_options, req);
 
-    let id = handle.id();
-    let mut client = create_persistent_subscriptions_client(handle);
+    let group_name = group_name.as_ref();
 
-    match client.get_info(req).await {
-        Err(e) => {
-            let e = crate::Error::from_grpc(e);
-            handle_error(&connection.sender, id, &e);
+    connection
+        .execute_idempotent(op_options, |handle| {
+            let req = new_request(connection.connection_settings(), op_options, req.clone());
+            let stream_name = stream_name.clone();
+
+            async move {
+                if !handle.supports_feature(Features::PERSISTENT_SUBSCRIPTION_MANAGEMENT) {
+                    if stream_name.is_all()
+                        && !handle.supports_feature(Features::PERSISTENT_SUBSCRIPITON_TO_ALL)
+                    {
+                        return Err(crate::Error::UnsupportedFeature);
+                    }
 
-            Err(e)
-        }
+                    return crate::http::persistent_subscriptions::get_persistent_subscription_info(
+                        &handle,
+                        http_client,
+                        connection.connection_settings(),
+                        stream_name,
+                        group_name,
+                        op_options,
+                    )
+                    .await;
+                }
 
-        Ok(resp) => {
-            let resp = resp.into_inner();
+                let mut client = create_persistent_subscriptions_client(handle);
+                let resp = client.get_info(req).await?.into_inner();
 
-            if let Some(info) = resp.subscription_info {
-                let sub: PersistentSubscriptionInfo<RevisionOrPosition> = info.try_into()?;
-                Ok(sub.map(|i| stream_name.select(i)))
-            } else {
-                Err(crate::Error::ResourceNotFound)
+                if let Some(info) = resp.subscription_info {
+                    let sub: PersistentSubscriptionInfo<RevisionOrPosition> = info.try_into()?;
+                    Ok(sub.map(|i| stream_name.select(i)))
+                } else {
+                    Err(crate::Error::ResourceNotFound)
+                }
             }
-        }
-    }
+        })
+        .await
 }
 
 pub async fn restart_persistent_subscription_subsystem(
@@ -1821,40 +2149,54 @@ pub async fn restart_persistent_subscription_subsystem(
     http_client: &reqwest::Client,
     op_options: &RestartPersistentSubscriptionSubsystem,
 ) -> crate::Result<()> {
-    let handle = connection.current_selected_node().await?;
-
-    if !handle.supports_feature(Features::PERSISTENT_SUBSCRIPTION_MANAGEMENT) {
-        return crate::http::persistent_subscriptions::restart_persistent_subscription_subsystem(
-            &handle,
-            http_client,
-            connection.connection_settings(),
-            op_options,
-        )
-        .await;
-    }
-
-    let id = handle.id();
-    let mut client = create_persistent_subscriptions_client(handle);
-    let req = new_request(connection.connection_settings(), op_options, ());
-
-    if let Err(e) = client.restart_subsystem(req).await {
-        let e = crate::Error::from_grpc(e);
-        handle_error(&connection.sender, id, &e);
+    connection
+        .execute(op_options, |handle| {
+            let req = new_request(connection.connection_settings(), op_options, ());
+
+            async move {
+                if !handle.supports_feature(Features::PERSISTENT_SUBSCRIPTION_MANAGEMENT) {
+                    return crate::http::persistent_subscriptions::restart_persistent_subscription_subsystem(
+                        &handle,
+                        http_client,
+                        connection.connection_settings(),
+                        op_options,
+                    )
+                    .await;
+                }
 
-        return Err(e);
-    }
+                let mut client = create_persistent_subscriptions_client(handle);
+                client.restart_subsystem(req).await?;
 
-    Ok(())
+                Ok(())
+            }
+        })
+        .await
 }
 
 fn create_streams_client(handle: Handle) -> StreamsClient<HyperClient> {
-    StreamsClient::with_origin(handle.client, handle.uri)
-        .max_decoding_message_size(client::MAX_RECEIVE_MESSAGE_SIZE)
+    let client = StreamsClient::with_origin(handle.client, handle.uri)
+        .max_decoding_message_size(client::MAX_RECEIVE_MESSAGE_SIZE);
+
+    #[cfg(feature = "compression")]
+    let client = match handle.compression.map(crate::compression::grpc_encoding) {
+        Some(encoding) => client.send_compressed(encoding).accept_compressed(encoding),
+        None => client,
+    };
+
+    client
 }
 
 fn create_persistent_subscriptions_client(
     handle: Handle,
 ) -> PersistentSubscriptionsClient<HyperClient> {
-    PersistentSubscriptionsClient::with_origin(handle.client, handle.uri)
-        .max_decoding_message_size(client::MAX_RECEIVE_MESSAGE_SIZE)
+    let client = PersistentSubscriptionsClient::with_origin(handle.client, handle.uri)
+        .max_decoding_message_size(client::MAX_RECEIVE_MESSAGE_SIZE);
+
+    #[cfg(feature = "compression")]
+    let client = match handle.compression.map(crate::compression::grpc_encoding) {
+        Some(encoding) => client.send_compressed(encoding).accept_compressed(encoding),
+        None => client,
+    };
+
+    client
 }
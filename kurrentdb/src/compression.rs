@@ -0,0 +1,405 @@
+//! Event payload compression.
+//!
+//! Compressed events store their payload in an encoded form and describe it in their custom
+//! metadata, a JSON object whose `$content-encoding` property names the payload algorithm. The
+//! original custom metadata, compressed or not, is kept base64-encoded under `$metadata`. Events
+//! read back by the client are decoded transparently, so [`crate::RecordedEvent::data`],
+//! [`crate::RecordedEvent::custom_metadata`] and [`crate::RecordedEvent::as_json`] see the
+//! original bytes.
+//!
+//! # Example
+//!
+//! ```no_run
+//! use kurrentdb::{EventData, PayloadCompression};
+//!
+//! # fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! let evt = EventData::json("document-uploaded", &serde_json::json!({ "body": "..." }))?
+//!     .compress(&PayloadCompression::zstd().threshold(4_096))?;
+//! # Ok(())
+//! # }
+//! ```
+use std::io::{self, Read, Write};
+
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD;
+use bytes::Bytes;
+use serde::{Deserialize, Serialize};
+use tracing::warn;
+
+use crate::{CompressionAlgorithm, EventData, RecordedEvent};
+
+const ENVELOPE_PREFIX: &[u8] = b"{\"$content-encoding\"";
+const CONTENT_TYPE: &str = "content-type";
+const JSON_CONTENT_TYPE: &str = "application/json";
+const BINARY_CONTENT_TYPE: &str = "application/octet-stream";
+
+/// Upper bound on the size of a decompressed payload or custom metadata, so a corrupted or
+/// malicious event can't exhaust memory.
+const MAX_DECOMPRESSED_SIZE: u64 = 64 * 1_024 * 1_024;
+
+/// Describes how [`EventData::compress`] compresses an event.
+#[derive(Clone, Debug)]
+pub struct PayloadCompression {
+    algorithm: CompressionAlgorithm,
+    level: Option<i32>,
+    threshold: usize,
+    metadata: bool,
+}
+
+impl PayloadCompression {
+    /// Payloads smaller than this number of bytes are stored as-is.
+    pub const DEFAULT_THRESHOLD: usize = 1_024;
+
+    pub fn new(algorithm: CompressionAlgorithm) -> Self {
+        Self {
+            algorithm,
+            level: None,
+            threshold: Self::DEFAULT_THRESHOLD,
+            metadata: false,
+        }
+    }
+
+    /// Compresses with gzip.
+    pub fn gzip() -> Self {
+        Self::new(CompressionAlgorithm::Gzip)
+    }
+
+    /// Compresses with zstd.
+    pub fn zstd() -> Self {
+        Self::new(CompressionAlgorithm::Zstd)
+    }
+
+    /// Compression level. Defaults to the algorithm's default level.
+    pub fn level(self, level: i32) -> Self {
+        Self {
+            level: Some(level),
+            ..self
+        }
+    }
+
+    /// Minimum size in bytes a payload must reach to be compressed. Default is
+    /// [`PayloadCompression::DEFAULT_THRESHOLD`].
+    pub fn threshold(self, threshold: usize) -> Self {
+        Self { threshold, ..self }
+    }
+
+    /// Also compresses the custom metadata when it reaches the threshold. Default is `false`.
+    pub fn metadata(self, metadata: bool) -> Self {
+        Self { metadata, ..self }
+    }
+
+    pub fn algorithm(&self) -> CompressionAlgorithm {
+        self.algorithm
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+struct Envelope {
+    #[serde(rename = "$content-encoding")]
+    content_encoding: Encoding,
+    #[serde(rename = "$content-type")]
+    content_type: String,
+    #[serde(rename = "$metadata-encoding", default)]
+    metadata_encoding: Encoding,
+    #[serde(rename = "$metadata", default, skip_serializing_if = "Option::is_none")]
+    metadata: Option<String>,
+}
+
+#[derive(Copy, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+enum Encoding {
+    #[default]
+    Identity,
+    Gzip,
+    Zstd,
+}
+
+impl From<CompressionAlgorithm> for Encoding {
+    fn from(algorithm: CompressionAlgorithm) -> Self {
+        match algorithm {
+            CompressionAlgorithm::Gzip => Encoding::Gzip,
+            CompressionAlgorithm::Zstd => Encoding::Zstd,
+        }
+    }
+}
+
+impl EventData {
+    /// Compresses this event payload, and optionally its custom metadata, when they reach the
+    /// configured threshold. The event is returned unchanged if nothing needs compressing. System
+    /// events, like links, are never compressed because the server interprets their payload.
+    pub fn compress(self, compression: &PayloadCompression) -> io::Result<EventData> {
+        let event_type = self.metadata.get("type").map(String::as_str);
+        if event_type.is_some_and(|t| t.starts_with('$')) {
+            return Ok(self);
+        }
+
+        let compress_data = self.payload.len() >= compression.threshold;
+        let compress_metadata = compression.metadata
+            && self
+                .custom_metadata
+                .as_ref()
+                .is_some_and(|m| m.len() >= compression.threshold);
+
+        if !compress_data && !compress_metadata {
+            return Ok(self);
+        }
+
+        let mut event = self;
+        let content_type = event
+            .metadata
+            .get(CONTENT_TYPE)
+            .cloned()
+            .unwrap_or_else(|| BINARY_CONTENT_TYPE.to_string());
+
+        let content_encoding = if compress_data {
+            event.payload = encode(compression, &event.payload)?;
+            compression.algorithm.into()
+        } else {
+            Encoding::Identity
+        };
+
+        let mut metadata_encoding = Encoding::Identity;
+        let metadata = match event.custom_metadata.take() {
+            Some(metadata) if compress_metadata => {
+                metadata_encoding = compression.algorithm.into();
+                Some(STANDARD.encode(encode(compression, &metadata)?))
+            }
+
+            Some(metadata) => Some(STANDARD.encode(metadata)),
+            None => None,
+        };
+
+        let envelope = Envelope {
+            content_encoding,
+            content_type,
+            metadata_encoding,
+            metadata,
+        };
+
+        event.custom_metadata = Some(serde_json::to_vec(&envelope)?.into());
+
+        // Compressed data is no longer JSON, the server must not treat it as such.
+        if compress_data {
+            event
+                .metadata
+                .insert(CONTENT_TYPE.to_string(), BINARY_CONTENT_TYPE.to_string());
+        }
+
+        Ok(event)
+    }
+}
+
+/// Restores the original payload and custom metadata of an event written with
+/// [`EventData::compress`]. Events that can't be decoded are returned as stored, with
+/// [`RecordedEvent::compression_error`] telling why.
+pub(crate) fn decode_event(mut event: RecordedEvent) -> RecordedEvent {
+    if !event.custom_metadata.starts_with(ENVELOPE_PREFIX) {
+        return event;
+    }
+
+    let decoded = serde_json::from_slice::<Envelope>(&event.custom_metadata)
+        .map_err(io::Error::from)
+        .and_then(|envelope| {
+            let data = decode(envelope.content_encoding, event.data.clone())?;
+            let metadata = match envelope.metadata {
+                Some(metadata) => {
+                    let metadata = STANDARD
+                        .decode(metadata)
+                        .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+
+                    decode(envelope.metadata_encoding, metadata.into())?
+                }
+
+                None => Bytes::new(),
+            };
+
+            Ok((data, metadata, envelope.content_type))
+        });
+
+    match decoded {
+        Ok((data, metadata, content_type)) => {
+            event.data = data;
+            event.custom_metadata = metadata;
+            event.is_json = content_type == JSON_CONTENT_TYPE;
+            event
+                .metadata
+                .insert(CONTENT_TYPE.to_string(), content_type);
+        }
+
+        Err(e) => {
+            warn!(
+                "Failed to decompress event {} of stream '{}': {}",
+                event.revision,
+                event.stream_id(),
+                e
+            );
+
+            event.compression_error = Some(e.to_string());
+        }
+    }
+
+    event
+}
+
+pub(crate) fn grpc_encoding(algorithm: CompressionAlgorithm) -> tonic::codec::CompressionEncoding {
+    match algorithm {
+        CompressionAlgorithm::Gzip => tonic::codec::CompressionEncoding::Gzip,
+        CompressionAlgorithm::Zstd => tonic::codec::CompressionEncoding::Zstd,
+    }
+}
+
+fn encode(compression: &PayloadCompression, bytes: &[u8]) -> io::Result<Bytes> {
+    let encoded = match compression.algorithm {
+        CompressionAlgorithm::Gzip => {
+            let level = compression
+                .level
+                .map(|l| flate2::Compression::new(l.clamp(0, 9) as u32))
+                .unwrap_or_default();
+            let mut encoder = flate2::write::GzEncoder::new(Vec::new(), level);
+            encoder.write_all(bytes)?;
+            encoder.finish()?
+        }
+
+        CompressionAlgorithm::Zstd => zstd::encode_all(bytes, compression.level.unwrap_or(0))?,
+    };
+
+    Ok(encoded.into())
+}
+
+fn decode(encoding: Encoding, bytes: Bytes) -> io::Result<Bytes> {
+    let decoder: Box<dyn Read + '_> = match encoding {
+        Encoding::Identity => return Ok(bytes),
+        Encoding::Gzip => Box::new(flate2::read::GzDecoder::new(&bytes[..])),
+        Encoding::Zstd => Box::new(zstd::stream::read::Decoder::new(&bytes[..])?),
+    };
+
+    let mut decoded = Vec::new();
+    decoder
+        .take(MAX_DECOMPRESSED_SIZE + 1)
+        .read_to_end(&mut decoded)?;
+
+    if decoded.len() as u64 > MAX_DECOMPRESSED_SIZE {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("decompressed size exceeds {} bytes", MAX_DECOMPRESSED_SIZE),
+        ));
+    }
+
+    Ok(decoded.into())
+}
+
+#[cfg(test)]
+mod compression_tests {
+    use super::*;
+    use crate::Position;
+    use std::collections::HashMap;
+
+    fn recorded(event: EventData) -> RecordedEvent {
+        RecordedEvent {
+            stream_id_raw: Bytes::from_static(b"documents-1"),
+            id: uuid::Uuid::new_v4(),
+            revision: 0,
+            is_json: event.metadata["content-type"] == JSON_CONTENT_TYPE,
+            event_type: event.metadata["type"].clone(),
+            data: event.payload,
+            metadata: HashMap::from([(
+                CONTENT_TYPE.to_string(),
+                event.metadata[CONTENT_TYPE].clone(),
+            )]),
+            custom_metadata: event.custom_metadata.unwrap_or_default(),
+            position: Position::start(),
+            created: Default::default(),
+            compression_error: None,
+        }
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let body = "lorem ipsum ".repeat(200);
+        let meta = serde_json::json!({ "author": "x".repeat(2_000) });
+
+        for compression in [PayloadCompression::gzip(), PayloadCompression::zstd()] {
+            let event = EventData::json("document-uploaded", &serde_json::json!({ "body": body }))
+                .unwrap()
+                .metadata_as_json(&meta)
+                .unwrap()
+                .compress(&compression.metadata(true))
+                .unwrap();
+
+            assert_eq!(event.metadata[CONTENT_TYPE], BINARY_CONTENT_TYPE);
+            assert!(event.payload.len() < body.len());
+
+            let event = decode_event(recorded(event));
+            let value = event.as_json::<serde_json::Value>().unwrap();
+
+            assert!(event.is_json);
+            assert_eq!(value["body"], body);
+            assert_eq!(
+                serde_json::from_slice::<serde_json::Value>(&event.custom_metadata).unwrap(),
+                meta
+            );
+        }
+    }
+
+    #[test]
+    fn test_below_threshold_is_untouched() {
+        let event = EventData::json("small", &serde_json::json!({ "a": 1 }))
+            .unwrap()
+            .compress(&PayloadCompression::zstd())
+            .unwrap();
+
+        assert!(event.custom_metadata.is_none());
+        assert_eq!(event.metadata[CONTENT_TYPE], JSON_CONTENT_TYPE);
+    }
+
+    #[test]
+    fn test_metadata_kept_when_only_data_is_compressed() {
+        let event = EventData::binary("blob", Bytes::from(vec![7u8; 4_096]))
+            .metadata(Bytes::from_static(b"raw"))
+            .compress(&PayloadCompression::gzip())
+            .unwrap();
+
+        let event = decode_event(recorded(event));
+
+        assert!(!event.is_json);
+        assert_eq!(event.data, Bytes::from(vec![7u8; 4_096]));
+        assert_eq!(event.custom_metadata, Bytes::from_static(b"raw"));
+    }
+
+    #[test]
+    fn test_undecodable_event_is_marked() {
+        let event = EventData::binary("blob", Bytes::from(vec![7u8; 4_096]))
+            .compress(&PayloadCompression::zstd())
+            .unwrap();
+        let mut stored = recorded(event);
+        stored.data = Bytes::from_static(b"not zstd");
+
+        let event = decode_event(stored);
+
+        assert!(event.compression_error().is_some());
+        assert_eq!(event.data, Bytes::from_static(b"not zstd"));
+    }
+
+    #[test]
+    fn test_decompression_is_capped() {
+        let bomb = Bytes::from(vec![0u8; MAX_DECOMPRESSED_SIZE as usize + 1]);
+        let event = EventData::binary("blob", bomb)
+            .compress(&PayloadCompression::zstd())
+            .unwrap();
+
+        assert!(event.payload.len() < 1_024 * 1_024);
+
+        let event = decode_event(recorded(event));
+
+        assert!(event.compression_error().is_some());
+    }
+
+    #[test]
+    fn test_links_are_never_compressed() {
+        let event = EventData::link("x".repeat(2_000), 1)
+            .compress(&PayloadCompression::zstd().threshold(0))
+            .unwrap();
+
+        assert!(event.custom_metadata.is_none());
+    }
+}
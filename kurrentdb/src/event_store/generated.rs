@@ -120,6 +120,99 @@ impl From<streams::append_resp::WrongExpectedVersion> for crate::Error {
     }
 }
 
+impl From<common::WrongExpectedVersion> for crate::Error {
+    fn from(value: common::WrongExpectedVersion) -> Self {
+        let current = match value.current_stream_revision_option {
+            Some(
+                common::wrong_expected_version::CurrentStreamRevisionOption::CurrentStreamRevision(
+                    rev,
+                ),
+            ) => CurrentRevision::Current(rev),
+            _ => CurrentRevision::NoStream,
+        };
+
+        let expected = match value.expected_stream_position_option {
+            Some(common::wrong_expected_version::ExpectedStreamPositionOption::ExpectedStreamPosition(rev)) => StreamState::StreamRevision(rev),
+            Some(common::wrong_expected_version::ExpectedStreamPositionOption::ExpectedStreamExists(_)) => StreamState::StreamExists,
+            Some(common::wrong_expected_version::ExpectedStreamPositionOption::ExpectedNoStream(_)) => StreamState::NoStream,
+            _ => StreamState::Any,
+        };
+
+        crate::Error::WrongExpectedVersion { current, expected }
+    }
+}
+
+/// Decodes the `google.rpc.Status` the server may send in the `grpc-status-details-bin` trailer.
+pub(crate) fn error_from_status_details(status: &tonic::Status) -> Option<crate::Error> {
+    use prost::Message;
+
+    if status.details().is_empty() {
+        return None;
+    }
+
+    let rpc_status = google_rpc::Status::decode(status.details()).ok()?;
+
+    error_from_rpc_details(rpc_status.details.as_ref()?, Some(Box::new(status.clone())))
+}
+
+/// Maps a known `event_store.client` error detail message into a typed error.
+pub(crate) fn error_from_rpc_details(
+    details: &prost_types::Any,
+    source: Option<Box<tonic::Status>>,
+) -> Option<crate::Error> {
+    use prost::Message;
+
+    let value = details.value.as_slice();
+
+    match details.type_url.rsplit(['/', '.']).next()? {
+        "WrongExpectedVersion" => common::WrongExpectedVersion::decode(value)
+            .ok()
+            .map(crate::Error::from),
+
+        "AccessDenied" => common::AccessDenied::decode(value)
+            .ok()
+            .map(|_| crate::Error::AccessDenied),
+
+        "Timeout" => common::Timeout::decode(value)
+            .ok()
+            .map(|_| crate::Error::DeadlineExceeded),
+
+        "StreamDeleted" => {
+            let details = common::StreamDeleted::decode(value).ok()?;
+            let stream = details
+                .stream_identifier
+                .map(|id| String::from_utf8_lossy(&id.stream_name).into_owned())
+                .unwrap_or_default();
+
+            Some(crate::Error::StreamDeleted { stream, source })
+        }
+
+        "InvalidTransaction" => common::InvalidTransaction::decode(value)
+            .ok()
+            .map(|_| crate::Error::InvalidTransaction { source }),
+
+        "MaximumAppendSizeExceeded" => {
+            let details = common::MaximumAppendSizeExceeded::decode(value).ok()?;
+
+            Some(crate::Error::MaximumAppendSizeExceeded {
+                max_append_size: details.max_append_size,
+                source,
+            })
+        }
+
+        "BadRequest" => {
+            let details = common::BadRequest::decode(value).ok()?;
+
+            Some(crate::Error::BadRequest {
+                message: details.message,
+                source,
+            })
+        }
+
+        _ => None,
+    }
+}
+
 impl From<streams::read_resp::read_event::RecordedEvent> for RecordedEvent {
     fn from(mut value: streams::read_resp::read_event::RecordedEvent) -> Self {
         let id = value.id.unwrap().try_into().unwrap();
@@ -149,7 +242,7 @@ impl From<streams::read_resp::read_event::RecordedEvent> for RecordedEvent {
             false
         };
 
-        RecordedEvent {
+        let event = RecordedEvent {
             id,
             stream_id_raw: value
                 .stream_identifier
@@ -163,7 +256,13 @@ impl From<streams::read_resp::read_event::RecordedEvent> for RecordedEvent {
             metadata: value.metadata,
             custom_metadata: value.custom_metadata,
             data: value.data,
-        }
+            compression_error: None,
+        };
+
+        #[cfg(feature = "compression")]
+        let event = crate::compression::decode_event(event);
+
+        event
     }
 }
 
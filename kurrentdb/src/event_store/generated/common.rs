@@ -78,6 +78,7 @@ pub struct StreamDeleted {
 }
 #[derive(Clone, Copy, PartialEq, ::prost::Message)]
 pub struct Timeout {}
+#[allow(dead_code)]
 #[derive(Clone, Copy, PartialEq, ::prost::Message)]
 pub struct Unknown {}
 #[derive(Clone, Copy, PartialEq, ::prost::Message)]
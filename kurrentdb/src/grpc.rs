@@ -1,14 +1,13 @@
 use rustls::pki_types::pem::PemObject;
 use std::cmp::Ordering;
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 use std::fmt::{Debug, Display};
 use std::str::FromStr;
-use std::sync::Once;
+use std::sync::{Arc, Once, RwLock};
 use std::time::Duration;
 use tracing::{debug, error, info, warn};
 
 use futures::Future;
-use hyper_rustls::HttpsConnector;
 use hyper_util::client::legacy::connect::HttpConnector;
 use nom::lib::std::fmt::Formatter;
 use rand::rngs::SmallRng;
@@ -22,14 +21,17 @@ use serde::{Deserialize, Serialize};
 use serde::{Deserializer, Serializer};
 use tokio::sync::mpsc::UnboundedSender;
 use tokio::sync::oneshot;
-use tonic::{Code, Status};
+use tonic::Code;
 use url::Url;
 use uuid::Uuid;
 
+use crate::channel::{Channel, ChannelPool};
 use crate::operations::gossip::{self, MemberInfo, VNodeState};
+use crate::options::retry::RetryPolicy;
+use crate::options::{OperationKind, Options};
 use crate::server_features::{Features, ServerInfo};
-use crate::types::{Endpoint, GrpcConnectionError};
-use crate::{Credentials, DnsClusterSettings, NodePreference};
+use crate::types::{Endpoint, GrpcConnectionError, TlsIdentity};
+use crate::{CompressionAlgorithm, Credentials, DnsClusterSettings, NodePreference};
 
 #[derive(Debug)]
 struct NoVerification;
@@ -238,6 +240,10 @@ fn default_gossip_timeout() -> Duration {
     ClientSettings::default().gossip_timeout
 }
 
+fn default_redirect_to_leader() -> bool {
+    true
+}
+
 fn default_preference() -> NodePreference {
     ClientSettings::default().preference
 }
@@ -258,11 +264,27 @@ fn default_keep_alive_timeout() -> Duration {
     ClientSettings::default().keep_alive_timeout
 }
 
+fn default_connection_pool_size() -> usize {
+    ClientSettings::default().connection_pool_size
+}
+
+fn default_max_retries() -> usize {
+    ClientSettings::default().max_retries
+}
+
+fn default_retry_backoff() -> Duration {
+    ClientSettings::default().retry_backoff
+}
+
+fn default_max_retry_backoff() -> Duration {
+    ClientSettings::default().max_retry_backoff
+}
+
 /// Gathers all the settings related to a gRPC client with an KurrentDB database.
 /// `ClientSettings` can only be created when parsing a connection string.
 ///
 /// ```
-/// # use kurrent::ClientSettings;
+/// # use kurrentdb::ClientSettings;
 /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
 /// let setts = "esdb://localhost:1234?tls=false".parse::<ClientSettings>()?;
 /// # Ok(())
@@ -273,7 +295,7 @@ fn default_keep_alive_timeout() -> Duration {
 /// For example, you can define a cluster-mode client based on a fixed set of gossip seeds:
 ///
 /// ```
-/// # use kurrent::ClientSettings;
+/// # use kurrentdb::ClientSettings;
 /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
 /// let setts = "esdb://localhost:1111,localhost:2222,localhost:3333".parse::<ClientSettings>()?;
 /// # Ok(())
@@ -283,7 +305,7 @@ fn default_keep_alive_timeout() -> Duration {
 /// Same example except we are using DNS discovery this time. The client will perform SRV queries
 /// to resolve all the node associated to that domain:
 /// ```
-/// # use kurrent::ClientSettings;
+/// # use kurrentdb::ClientSettings;
 /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
 /// let setts = "esdb+discover://mydomain:1234".parse::<ClientSettings>()?;
 /// # Ok(())
@@ -294,20 +316,20 @@ fn default_keep_alive_timeout() -> Duration {
 /// connection string, that setting default value is used.
 ///
 /// * `maxDiscoverAttempts`: default `3`. Maximum number of DNS discovery attempts before the
-///    connection gives up.
+///   connection gives up.
 ///
 /// * `discoveryInterval`: default `500ms`. Waiting period between discovery attempts.
 ///
 /// * `gossipTimeout`: default `3s`: Waiting period before a gossip request timeout.
-///    __*TODO - Current behavior doesn't timeout at all.*__
+///   __*TODO - Current behavior doesn't timeout at all.*__
 ///
 /// * `tls`: default `true`. Use a secure connection.
 ///
 /// * `tlsVerifyCert`: default `true`. When using a secure connection, perform a certification
-///    verification.
+///   verification.
 ///
 /// * `nodePreference`: default `random`. When in a cluster connection, indicates what type of node
-///    a connection should pick. Keep in mind that's best effort. Supported values are:
+///   a connection should pick. Keep in mind that's best effort. Supported values are:
 ///    * `leader`
 ///    * `random`
 ///    * `follower`
@@ -315,6 +337,21 @@ fn default_keep_alive_timeout() -> Duration {
 ///
 /// * `keepAliveInterval`: default `10s`
 /// * `keepAliveTimeout`: default `10s`
+///
+/// * `connectionPoolSize`: default `1`. How many HTTP/2 connections are opened to the selected
+///   node. Each command goes through the connection with the fewest calls in flight.
+/// * `streamingConnectionPoolSize`: default `0`. How many extra HTTP/2 connections are dedicated
+///   to streaming operations, like reads and subscriptions. When `0`, streaming operations share
+///   the regular connections.
+/// * `grpcCompression`: default `none`. Compresses gRPC messages of streams and persistent
+///   subscriptions calls. Supported values are `none`, `gzip` and `zstd`, the server must support
+///   the chosen algorithm. Requires the `compression` feature.
+///
+/// * `maxRetries`: default `0`. How many times a failing unary command is retried, see
+///   [`crate::RetryPolicy`].
+/// * `retryBackoff`: default `100ms`. Waiting period before the first retry, doubling on each
+///   following attempt.
+/// * `maxRetryBackoff`: default `5s`. Upper bound of the waiting period between two attempts.
 #[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub struct ClientSettings {
     #[serde(default)]
@@ -361,10 +398,33 @@ pub struct ClientSettings {
         deserialize_with = "deserialize_optional_duration"
     )]
     pub(crate) default_deadline: Option<Duration>,
+    #[serde(default = "default_connection_pool_size")]
+    pub(crate) connection_pool_size: usize,
+    #[serde(default)]
+    pub(crate) streaming_connection_pool_size: usize,
+    #[serde(default)]
+    pub(crate) grpc_compression: Option<CompressionAlgorithm>,
+    #[serde(default = "default_max_retries")]
+    pub(crate) max_retries: usize,
+    #[serde(
+        default = "default_retry_backoff",
+        serialize_with = "serialize_duration",
+        deserialize_with = "deserialize_duration"
+    )]
+    pub(crate) retry_backoff: Duration,
+    #[serde(
+        default = "default_max_retry_backoff",
+        serialize_with = "serialize_duration",
+        deserialize_with = "deserialize_duration"
+    )]
+    pub(crate) max_retry_backoff: Duration,
     pub(crate) connection_name: Option<String>,
     pub(crate) tls_ca_file: Option<String>,
     pub(crate) user_cert_file: Option<String>,
     pub(crate) user_key_file: Option<String>,
+    // Only clients pinned to a node don't follow the leader a `NotLeader` error points to.
+    #[serde(skip, default = "default_redirect_to_leader")]
+    pub(crate) redirect_to_leader: bool,
 }
 
 impl ClientSettings {
@@ -404,6 +464,27 @@ impl ClientSettings {
         &self.default_user_name
     }
 
+    pub fn connection_pool_size(&self) -> usize {
+        self.connection_pool_size
+    }
+
+    pub fn streaming_connection_pool_size(&self) -> usize {
+        self.streaming_connection_pool_size
+    }
+
+    /// Compression applied to gRPC messages, if any.
+    pub fn grpc_compression(&self) -> Option<CompressionAlgorithm> {
+        self.grpc_compression
+    }
+
+    /// Retry policy applied to unary commands that don't override it.
+    pub fn retry_policy(&self) -> RetryPolicy {
+        RetryPolicy::default()
+            .max_retries(self.max_retries)
+            .initial_backoff(self.retry_backoff)
+            .max_backoff(self.max_retry_backoff)
+    }
+
     pub fn to_uri(&self, endpoint: &Endpoint) -> http::Uri {
         let scheme = if self.secure { "https" } else { "http" };
 
@@ -649,6 +730,58 @@ fn parse_from_url(
                 result.default_deadline = Some(Duration::from_millis(value as u64));
             }
 
+            "connectionpoolsize" => {
+                let value = parse_param::<usize>(name, value)?;
+
+                if value == 0 {
+                    return Err(ClientSettingsParseError {
+                        message:
+                            "Invalid connectionPoolSize of 0. At least one connection is required"
+                                .to_string(),
+                        error: None,
+                    });
+                }
+
+                result.connection_pool_size = value;
+            }
+
+            "streamingconnectionpoolsize" => {
+                result.streaming_connection_pool_size = parse_param(name, value)?;
+            }
+
+            "grpccompression" => match value.to_lowercase().as_str() {
+                "none" => {
+                    result.grpc_compression = None;
+                }
+
+                "gzip" => {
+                    result.grpc_compression = Some(CompressionAlgorithm::Gzip);
+                }
+
+                "zstd" => {
+                    result.grpc_compression = Some(CompressionAlgorithm::Zstd);
+                }
+
+                unknown => {
+                    return Err(ClientSettingsParseError {
+                        message: format!("Unknown gRPC compression value '{}'", unknown),
+                        error: None,
+                    });
+                }
+            },
+
+            "maxretries" => {
+                result.max_retries = parse_param(name, value)?;
+            }
+
+            "retrybackoff" => {
+                result.retry_backoff = Duration::from_millis(parse_param(name, value)?);
+            }
+
+            "maxretrybackoff" => {
+                result.max_retry_backoff = Duration::from_millis(parse_param(name, value)?);
+            }
+
             "connectionname" => {
                 result.connection_name = Some(value.to_string());
             }
@@ -798,10 +931,17 @@ impl Default for ClientSettings {
             keep_alive_interval: Duration::from_millis(self::defaults::KEEP_ALIVE_INTERVAL_IN_MS),
             keep_alive_timeout: Duration::from_millis(self::defaults::KEEP_ALIVE_TIMEOUT_IN_MS),
             default_deadline: None,
+            connection_pool_size: 1,
+            streaming_connection_pool_size: 0,
+            grpc_compression: None,
+            max_retries: RetryPolicy::default().max_retries,
+            retry_backoff: RetryPolicy::default().initial_backoff,
+            max_retry_backoff: RetryPolicy::default().max_backoff,
             connection_name: None,
             user_cert_file: None,
             user_key_file: None,
             tls_ca_file: None,
+            redirect_to_leader: true,
         }
     }
 }
@@ -811,12 +951,84 @@ pub(crate) mod defaults {
     pub const KEEP_ALIVE_TIMEOUT_IN_MS: u64 = 10_000;
 }
 
-pub(crate) type HyperClient =
-    hyper_util::client::legacy::Client<HttpsConnector<HttpConnector>, tonic::body::BoxBody>;
+pub(crate) type HyperClient = Channel;
+
+/// Creates the HTTP/2 client used to reach nodes. When an identity is given, it is used as TLS
+/// client certificate instead of the one from the connection settings.
+pub(crate) fn create_hyper_client(
+    settings: &ClientSettings,
+    identity: Option<&TlsIdentity>,
+) -> Result<HyperClient, rustls::Error> {
+    let mut roots = rustls::RootCertStore::empty();
+
+    RUSTLS_INIT.call_once(|| {
+        rustls::crypto::aws_lc_rs::default_provider()
+            .install_default()
+            .expect("failed to install rustls crypto provider");
+    });
+
+    if let Some(cert) = settings.tls_ca_file() {
+        let cert_chain: Result<Vec<CertificateDer<'_>>, _> =
+            CertificateDer::pem_file_iter(cert).unwrap().collect();
+
+        for cert in cert_chain.unwrap() {
+            roots.add(cert).unwrap();
+        }
+    } else {
+        for cert in rustls_native_certs::load_native_certs().expect("could not load platform certs")
+        {
+            roots.add(cert).unwrap();
+        }
+    }
+
+    let tls = tokio_rustls::rustls::ClientConfig::builder().with_root_certificates(roots);
+
+    let mut tls = if let Some(identity) = identity {
+        tls.with_client_auth_cert(identity.cert_chain.clone(), identity.key.clone_key())?
+    } else if let Some((cert, key)) = settings.user_certificate() {
+        let cert_chain: Result<Vec<CertificateDer<'_>>, _> =
+            CertificateDer::pem_file_iter(cert).unwrap().collect();
+
+        tls.with_client_auth_cert(
+            cert_chain.unwrap(),
+            PrivateKeyDer::from_pem_file(key).unwrap(),
+        )
+        .unwrap()
+    } else {
+        tls.with_no_client_auth()
+    };
+
+    if !settings.tls_verify_cert && settings.secure {
+        tls.dangerous()
+            .set_certificate_verifier(std::sync::Arc::new(NoVerification));
+    }
+
+    let mut http = HttpConnector::new();
+    http.enforce_http(false);
+
+    let connector = tower::ServiceBuilder::new()
+        .layer_fn(move |s| {
+            hyper_rustls::HttpsConnectorBuilder::new()
+                .with_tls_config(tls.clone())
+                .https_or_http()
+                .enable_http2()
+                .wrap_connector(s)
+        })
+        .service(http);
+
+    let client = hyper_util::client::legacy::Client::builder(hyper_util::rt::TokioExecutor::new())
+        .timer(hyper_util::rt::tokio::TokioTimer::new())
+        .http2_only(true)
+        .http2_keep_alive_interval(settings.keep_alive_interval)
+        .http2_keep_alive_timeout(settings.keep_alive_timeout)
+        .build::<_, tonic::body::BoxBody>(connector);
+
+    Ok(Channel::new(client))
+}
 
 struct NodeConnection {
     id: Uuid,
-    client: HyperClient,
+    pool: ChannelPool,
     handle: Option<HandleInfo>,
     settings: ClientSettings,
     cluster_mode: Option<ClusterMode>,
@@ -838,7 +1050,7 @@ struct NodeRequest {
 #[derive(Clone)]
 pub(crate) struct HandleInfo {
     id: Uuid,
-    pub(crate) client: HyperClient,
+    pub(crate) pool: ChannelPool,
     pub(crate) uri: hyper::Uri,
     pub(crate) endpoint: Endpoint,
     pub(crate) secure: bool,
@@ -849,69 +1061,16 @@ static RUSTLS_INIT: Once = Once::new();
 
 impl NodeConnection {
     fn new(settings: ClientSettings) -> Self {
-        let mut roots = rustls::RootCertStore::empty();
-
-        RUSTLS_INIT.call_once(|| {
-            rustls::crypto::aws_lc_rs::default_provider()
-                .install_default()
-                .expect("failed to install rustls crypto provider");
-        });
-
-        if let Some(cert) = settings.tls_ca_file() {
-            let cert_chain: Result<Vec<CertificateDer<'_>>, _> =
-                CertificateDer::pem_file_iter(cert).unwrap().collect();
-
-            for cert in cert_chain.unwrap() {
-                roots.add(cert).unwrap();
-            }
-        } else {
-            for cert in
-                rustls_native_certs::load_native_certs().expect("could not load platform certs")
-            {
-                roots.add(cert).unwrap();
-            }
-        }
-
-        let tls = tokio_rustls::rustls::ClientConfig::builder().with_root_certificates(roots);
-
-        let mut tls = if let Some((cert, key)) = settings.user_certificate() {
-            let cert_chain: Result<Vec<CertificateDer<'_>>, _> =
-                CertificateDer::pem_file_iter(cert).unwrap().collect();
-
-            tls.with_client_auth_cert(
-                cert_chain.unwrap(),
-                PrivateKeyDer::from_pem_file(key).unwrap(),
-            )
-            .unwrap()
-        } else {
-            tls.with_no_client_auth()
+        // Each channel is a distinct HTTP/2 client, hence a distinct connection to the node.
+        let create_channels = |count| {
+            (0..count)
+                .map(|_| create_hyper_client(&settings, None).unwrap())
+                .collect::<Vec<_>>()
         };
-
-        if !settings.tls_verify_cert && settings.secure {
-            tls.dangerous()
-                .set_certificate_verifier(std::sync::Arc::new(NoVerification));
-        }
-
-        let mut http = HttpConnector::new();
-        http.enforce_http(false);
-
-        let connector = tower::ServiceBuilder::new()
-            .layer_fn(move |s| {
-                hyper_rustls::HttpsConnectorBuilder::new()
-                    .with_tls_config(tls.clone())
-                    .https_or_http()
-                    .enable_http2()
-                    .wrap_connector(s)
-            })
-            .service(http);
-
-        let client =
-            hyper_util::client::legacy::Client::builder(hyper_util::rt::TokioExecutor::new())
-                .timer(hyper_util::rt::tokio::TokioTimer::new())
-                .http2_only(true)
-                .http2_keep_alive_interval(settings.keep_alive_interval)
-                .http2_keep_alive_timeout(settings.keep_alive_timeout)
-                .build::<_, tonic::body::BoxBody>(connector);
+        let pool = ChannelPool::new(
+            create_channels(settings.connection_pool_size.max(1)),
+            create_channels(settings.streaming_connection_pool_size),
+        );
 
         let cluster_mode = if settings.dns_discover || settings.hosts().len() > 1 {
             let mode = if settings.dns_discover {
@@ -928,7 +1087,7 @@ impl NodeConnection {
 
         Self {
             id: Uuid::nil(),
-            client,
+            pool,
             handle: None,
             settings,
             cluster_mode,
@@ -947,14 +1106,17 @@ impl NodeConnection {
 
         loop {
             if let Some(request) = request.take() {
-                if self.id != request.correlation {
-                    if let Some(handle) = self.handle.clone() {
-                        return Ok(handle);
-                    }
+                if self.id != request.correlation
+                    && let Some(handle) = self.handle.clone()
+                {
+                    return Ok(handle);
                 }
 
                 failed_endpoint = self.handle.take().map(|h| h.endpoint);
-                selected_node = Some(request.endpoint);
+
+                if self.settings.redirect_to_leader {
+                    selected_node = Some(request.endpoint);
+                }
 
                 continue;
             } else if let Some(handle) = self.handle.clone() {
@@ -971,7 +1133,10 @@ impl NodeConnection {
                     );
                     let server_info = match tokio::time::timeout(
                         self.settings.gossip_timeout(),
-                        crate::server_features::supported_methods(&self.client, uri.clone()),
+                        crate::server_features::supported_methods(
+                            self.pool.pick(OperationKind::Regular),
+                            uri.clone(),
+                        ),
                     )
                     .await
                     {
@@ -1011,7 +1176,7 @@ impl NodeConnection {
                         id: self.id,
                         endpoint: selected_node,
                         secure: self.settings.secure,
-                        client: self.client.clone(),
+                        pool: self.pool.clone(),
                         uri,
                         server_info,
                     };
@@ -1025,7 +1190,7 @@ impl NodeConnection {
                     let node = node_selection(
                         &self.settings,
                         mode,
-                        &self.client,
+                        &self.pool.pick(OperationKind::Regular),
                         &failed_endpoint,
                         &mut self.rng,
                         &mut self.previous_candidates,
@@ -1063,6 +1228,8 @@ fn connection_state_machine(
 ) -> UnboundedSender<Msg> {
     let (sender, mut consumer) = tokio::sync::mpsc::unbounded_channel::<Msg>();
     let dup_sender = sender.clone();
+    #[cfg(feature = "compression")]
+    let compression = settings.grpc_compression;
 
     handle.spawn(async move {
         let mut connection = NodeConnection::new(settings);
@@ -1094,12 +1261,15 @@ fn connection_state_machine(
 
                             let handle = Handle {
                                 id: info.id,
-                                client: info.client,
+                                client: info.pool.pick(OperationKind::Regular),
+                                pool: info.pool,
                                 uri: info.uri,
                                 endpoint: info.endpoint,
                                 secure: info.secure,
                                 sender: sender.clone(),
                                 server_info: info.server_info,
+                                #[cfg(feature = "compression")]
+                                compression,
                             };
 
                             handle_opt = Some(handle.clone());
@@ -1128,12 +1298,15 @@ fn connection_state_machine(
 
                             let handle = Handle {
                                 id: info.id,
-                                client: info.client,
+                                client: info.pool.pick(OperationKind::Regular),
+                                pool: info.pool,
                                 uri: info.uri,
                                 endpoint: info.endpoint,
                                 secure: info.secure,
                                 sender: sender.clone(),
                                 server_info: info.server_info,
+                                #[cfg(feature = "compression")]
+                                compression,
                             };
 
                             handle_opt = Some(handle);
@@ -1151,11 +1324,14 @@ fn connection_state_machine(
 pub(crate) struct Handle {
     id: Uuid,
     pub(crate) client: HyperClient,
+    pool: ChannelPool,
     pub(crate) uri: hyper::Uri,
     pub(crate) endpoint: Endpoint,
     pub(crate) secure: bool,
     pub(crate) server_info: ServerInfo,
     sender: tokio::sync::mpsc::UnboundedSender<Msg>,
+    #[cfg(feature = "compression")]
+    pub(crate) compression: Option<CompressionAlgorithm>,
 }
 
 impl Handle {
@@ -1210,6 +1386,9 @@ impl std::fmt::Debug for Msg {
 pub struct GrpcClient {
     pub(crate) sender: tokio::sync::mpsc::UnboundedSender<Msg>,
     connection_settings: ClientSettings,
+    // Each identity gets its own HTTP/2 client, thus its own connections, as the client
+    // certificate is negotiated during the TLS handshake.
+    tls_identities: Arc<RwLock<HashMap<String, HyperClient>>>,
 }
 
 impl GrpcClient {
@@ -1219,27 +1398,185 @@ impl GrpcClient {
         GrpcClient {
             sender,
             connection_settings,
+            tls_identities: Default::default(),
+        }
+    }
+
+    /// Returns a client whose connection only ever goes to the given node, with a copy of this
+    /// client's TLS identities.
+    pub(crate) fn pinned_to(&self, endpoint: &Endpoint) -> Self {
+        let connection_settings = ClientSettings {
+            dns_discover: false,
+            hosts: vec![endpoint.clone()],
+            // A leader preference would make followers reject every command.
+            preference: NodePreference::Random,
+            redirect_to_leader: false,
+            ..self.connection_settings.clone()
+        };
+        let tls_identities = self.tls_identities.read().unwrap().clone();
+
+        GrpcClient {
+            sender: connection_state_machine(
+                tokio::runtime::Handle::current(),
+                connection_settings.clone(),
+            ),
+            connection_settings,
+            tls_identities: Arc::new(RwLock::new(tls_identities)),
+        }
+    }
+
+    pub(crate) fn add_tls_identity(
+        &self,
+        name: String,
+        identity: &TlsIdentity,
+    ) -> crate::Result<()> {
+        if !self.connection_settings.secure {
+            return Err(crate::Error::IllegalStateError(
+                "TLS identities require a secure connection".to_string(),
+            ));
         }
+
+        let client = create_hyper_client(&self.connection_settings, Some(identity))
+            .map_err(|e| crate::Error::InitializationError(e.to_string()))?;
+
+        self.tls_identities.write().unwrap().insert(name, client);
+
+        Ok(())
     }
 
-    pub(crate) async fn execute<F, Fut, A>(&self, action: F) -> crate::Result<A>
+    pub(crate) fn remove_tls_identity(&self, name: &str) -> bool {
+        self.tls_identities.write().unwrap().remove(name).is_some()
+    }
+
+    /// Returns the selected node handle, going through the least loaded channel for that kind of
+    /// operation, or through the connections of the given TLS identity if any.
+    pub(crate) async fn select_node(
+        &self,
+        kind: OperationKind,
+        tls_identity: Option<&str>,
+    ) -> crate::Result<Handle> {
+        let mut handle = self.current_selected_node().await?;
+
+        handle.client = handle.pool.pick(kind);
+
+        if let Some(name) = tls_identity {
+            handle.client = self
+                .tls_identities
+                .read()
+                .unwrap()
+                .get(name)
+                .cloned()
+                .ok_or_else(|| {
+                    crate::Error::IllegalStateError(format!("Unknown TLS identity '{}'", name))
+                })?;
+        }
+
+        Ok(handle)
+    }
+
+    /// Runs a unary command against the selected node. The command is retried, according to the
+    /// operation retry policy, only on errors where the server didn't process it: when no node
+    /// could be selected or when the server rejected it, see [`crate::Error::is_retryable`].
+    pub(crate) async fn execute<O, F, Fut, A>(&self, options: &O, action: F) -> crate::Result<A>
+    where
+        O: Options,
+        F: FnMut(Handle) -> Fut,
+        Fut: Future<Output = crate::Result<A>>,
+    {
+        self.execute_with_retry(options, false, action).await
+    }
+
+    /// Like [`GrpcClient::execute`] but because the command is idempotent, it is also retried on
+    /// transient errors.
+    pub(crate) async fn execute_idempotent<O, F, Fut, A>(
+        &self,
+        options: &O,
+        action: F,
+    ) -> crate::Result<A>
     where
-        F: FnOnce(Handle) -> Fut + Send,
-        Fut: Future<Output = Result<A, Status>> + Send,
-        A: Send,
+        O: Options,
+        F: FnMut(Handle) -> Fut,
+        Fut: Future<Output = crate::Result<A>>,
     {
-        debug!("Sending channel handle request...");
-        let handle = self.current_selected_node().await?;
-        debug!("Handle received!");
+        self.execute_with_retry(options, true, action).await
+    }
 
+    /// Runs a unary command that only affects the node it runs on. Errors still trigger a
+    /// reconnection but the command is never retried, as the retry could land on another node.
+    pub(crate) async fn execute_once<O, F, Fut, A>(
+        &self,
+        options: &O,
+        action: F,
+    ) -> crate::Result<A>
+    where
+        O: Options,
+        F: FnOnce(Handle) -> Fut,
+        Fut: Future<Output = crate::Result<A>>,
+    {
+        let tls_identity = options.common_operation_options().tls_identity.as_deref();
+        let handle = self.select_node(options.kind(), tls_identity).await?;
         let id = handle.id;
-        action(handle).await.map_err(|status| {
-            let e = crate::Error::from_grpc(status);
-            handle_error(&self.sender, id, &e);
-            e
+
+        action(handle).await.inspect_err(|e| {
+            handle_error(&self.sender, id, e);
         })
     }
 
+    async fn execute_with_retry<O, F, Fut, A>(
+        &self,
+        options: &O,
+        idempotent: bool,
+        mut action: F,
+    ) -> crate::Result<A>
+    where
+        O: Options,
+        F: FnMut(Handle) -> Fut,
+        Fut: Future<Output = crate::Result<A>>,
+    {
+        let policy = options
+            .common_operation_options()
+            .retry_policy
+            .unwrap_or_else(|| self.connection_settings.retry_policy());
+        let mut attempt = 0;
+
+        loop {
+            debug!("Sending channel handle request...");
+            let tls_identity = options.common_operation_options().tls_identity.as_deref();
+            // When no node could be selected, the command never left the client.
+            let (result, sent) = match self.select_node(options.kind(), tls_identity).await {
+                Err(e) => (Err(e), false),
+                Ok(handle) => {
+                    debug!("Handle received!");
+                    let id = handle.id;
+                    let result = action(handle).await.inspect_err(|e| {
+                        handle_error(&self.sender, id, e);
+                    });
+
+                    (result, true)
+                }
+            };
+
+            match result {
+                Err(e)
+                    if attempt < policy.max_retries
+                        && (e.is_retryable() || (idempotent || !sent) && e.is_transient()) =>
+                {
+                    let delay = policy.backoff(attempt);
+                    attempt += 1;
+
+                    warn!(
+                        "Command failed, retrying in {:?} ({}/{}): {}",
+                        delay, attempt, policy.max_retries, e
+                    );
+
+                    tokio::time::sleep(delay).await;
+                }
+
+                result => return result,
+            }
+        }
+    }
+
     pub(crate) async fn current_selected_node(&self) -> crate::Result<Handle> {
         let (sender, consumer) = tokio::sync::oneshot::channel();
 
@@ -1248,7 +1585,13 @@ impl GrpcClient {
         }
 
         match consumer.await {
-            Ok(handle) => handle.map_err(crate::Error::GrpcConnectionError),
+            Ok(handle) => {
+                let mut handle = handle.map_err(crate::Error::GrpcConnectionError)?;
+                handle.client = handle.pool.pick(OperationKind::Regular);
+
+                Ok(handle)
+            }
+
             Err(_) => Err(crate::Error::ConnectionClosed),
         }
     }
@@ -1273,7 +1616,7 @@ pub(crate) fn handle_error(sender: &UnboundedSender<Msg>, connection_id: Uuid, e
             "NotLeaderException found. Start reconnection process on: {:?}",
             leader
         );
-    } else if let crate::Error::Grpc { code, message } = err {
+    } else if let crate::Error::Grpc { code, message, .. } = err {
         debug!(
             "Operation unexpected error: code: {}, message: {}",
             code, message
@@ -1437,7 +1780,7 @@ fn determine_best_node(
 
     let member_opt = members.min_by(|a, b| {
         if let NodePreference::Random = preference {
-            if rng.next_u32() % 2 == 0 {
+            if rng.next_u32().is_multiple_of(2) {
                 return Ordering::Greater;
             }
 
@@ -1445,7 +1788,7 @@ fn determine_best_node(
         }
 
         if preference.match_preference(&a.state) && preference.match_preference(&b.state) {
-            if rng.next_u32() % 2 == 0 {
+            if rng.next_u32().is_multiple_of(2) {
                 return Ordering::Less;
             } else {
                 return Ordering::Greater;
@@ -1,6 +1,16 @@
 use tracing::error;
 pub mod persistent_subscriptions;
 
+pub(crate) fn create_http_client(
+    settings: &crate::ClientSettings,
+) -> crate::Result<reqwest::Client> {
+    reqwest::Client::builder()
+        .danger_accept_invalid_certs(!settings.is_tls_certificate_verification_enabled())
+        .https_only(settings.is_secure_mode_enabled())
+        .build()
+        .map_err(|e| crate::Error::InitializationError(e.to_string()))
+}
+
 pub fn http_configure_auth(
     builder: reqwest::RequestBuilder,
     creds_opt: Option<&crate::Credentials>,
@@ -11,7 +11,7 @@
 //! # Example
 //!
 //! ```no_run
-//! use eventstore::{ Client, EventData };
+//! use kurrentdb::{ Client, EventData };
 //! use serde::{Serialize, Deserialize};
 //!
 //! #[derive(Serialize, Deserialize, Debug)]
@@ -56,8 +56,13 @@
 //! [KurrentDB]: https://eventstore.com/
 //! [eventstoredb docs]: https://developers.eventstore.com/server/20.6/server/installation/
 mod batch;
+#[cfg(feature = "blocking")]
+pub mod blocking;
+mod channel;
 mod client;
 mod commands;
+#[cfg(feature = "compression")]
+mod compression;
 mod event_store;
 mod grpc;
 mod http;
@@ -77,7 +82,12 @@ pub(crate) mod google {
 
 pub use batch::*;
 pub use client::Client;
-pub use commands::{PersistentSubscription, ReadEvent, ReadStream, Subscription};
+pub use commands::{
+    CollectLimits, CollectedEvents, MultiStreamSubscription, PersistentSubscription, ReadEvent,
+    ReadStream, Subscription,
+};
+#[cfg(feature = "compression")]
+pub use compression::PayloadCompression;
 pub use grpc::{ClientSettings, ClientSettingsParseError};
 pub use options::append_to_stream::*;
 pub use options::batch_append::*;
@@ -85,18 +95,27 @@ pub use options::delete_stream::*;
 pub use options::persistent_subscription::*;
 pub use options::projections::*;
 pub use options::read_all::*;
+pub use options::read_event::*;
 pub use options::read_stream::*;
 pub use options::retry::*;
 pub use options::subscribe_to_all::*;
 pub use options::subscribe_to_stream::*;
+pub use options::subscribe_to_streams::*;
+pub use options::system_settings::*;
 pub use options::tombstone_stream::*;
+pub use options::update_stream_metadata::*;
 pub use projection_client::*;
 pub use types::*;
 
 pub mod prelude {
     pub use crate::batch::*;
     pub use crate::client::Client;
-    pub use crate::commands::{PersistentSubscription, ReadEvent, ReadStream, Subscription};
+    pub use crate::commands::{
+        CollectLimits, CollectedEvents, MultiStreamSubscription, PersistentSubscription, ReadEvent,
+        ReadStream, Subscription,
+    };
+    #[cfg(feature = "compression")]
+    pub use crate::compression::PayloadCompression;
     pub use crate::grpc::{ClientSettings, ClientSettingsParseError};
     pub use crate::options::append_to_stream::*;
     pub use crate::options::batch_append::*;
@@ -104,11 +123,15 @@ pub mod prelude {
     pub use crate::options::persistent_subscription::*;
     pub use crate::options::projections::*;
     pub use crate::options::read_all::*;
+    pub use crate::options::read_event::*;
     pub use crate::options::read_stream::*;
     pub use crate::options::retry::*;
     pub use crate::options::subscribe_to_all::*;
     pub use crate::options::subscribe_to_stream::*;
+    pub use crate::options::subscribe_to_streams::*;
+    pub use crate::options::system_settings::*;
     pub use crate::options::tombstone_stream::*;
+    pub use crate::options::update_stream_metadata::*;
     pub use crate::projection_client::*;
     pub use crate::types::*;
 }
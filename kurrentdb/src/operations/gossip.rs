@@ -13,7 +13,7 @@ pub async fn read(
     client: &HyperClient,
     uri: hyper::Uri,
 ) -> Result<Vec<MemberInfo>, Status> {
-    let inner = wire::gossip_client::GossipClient::with_origin(client, uri);
+    let inner = wire::gossip_client::GossipClient::with_origin(client.clone(), uri);
     let mut req = Request::new(());
 
     *req.metadata_mut() = build_request_metadata(settings, &Default::default());
@@ -172,6 +172,7 @@ pub enum VNodeState {
 }
 
 impl VNodeState {
+    #[allow(clippy::result_large_err)]
     pub fn from_i32(value: i32) -> Result<Self, Status> {
         match value {
             0 => Ok(VNodeState::Initializing),
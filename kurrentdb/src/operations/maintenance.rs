@@ -0,0 +1,295 @@
+//! Takes a cluster node out of the way before maintenance, following the usual runbook: lower its
+//! priority, resign it if it's the leader, then wait for a new leader and for the followers to
+//! catch up with what the leader had written at that point.
+use std::time::Duration;
+
+use eventstore_macros::options;
+use futures::stream::BoxStream;
+use tracing::debug;
+
+use super::{Client, MemberInfo, OperationalOptions, VNodeState};
+use crate::Endpoint;
+
+options! {
+    #[derive(Clone)]
+    /// Options of [`Client::prepare_node_for_maintenance`].
+    pub struct MaintenanceOptions {
+        pub(crate) priority: i32,
+        pub(crate) step_timeout: Duration,
+        pub(crate) poll_interval: Duration,
+        pub(crate) max_replication_lag: i64,
+    }
+}
+
+impl Default for MaintenanceOptions {
+    fn default() -> Self {
+        Self {
+            priority: -1,
+            step_timeout: Duration::from_secs(60),
+            poll_interval: Duration::from_secs(1),
+            max_replication_lag: 0,
+            common_operation_options: Default::default(),
+        }
+    }
+}
+
+impl MaintenanceOptions {
+    /// Priority given to the node. Nodes with a lower priority are less likely to be elected
+    /// leader. Default is `-1`, below the server default of `0`.
+    pub fn priority(self, priority: i32) -> Self {
+        Self { priority, ..self }
+    }
+
+    /// How long to wait for a new leader, then for the followers to catch up, before giving up
+    /// with [`crate::Error::DeadlineExceeded`]. Default is 60 seconds per step.
+    pub fn step_timeout(self, step_timeout: Duration) -> Self {
+        Self {
+            step_timeout,
+            ..self
+        }
+    }
+
+    /// Delay between two gossip reads while waiting. Default is 1 second.
+    pub fn poll_interval(self, poll_interval: Duration) -> Self {
+        Self {
+            poll_interval,
+            ..self
+        }
+    }
+
+    /// How many bytes a follower writer checkpoint can be behind the leader's, as recorded when
+    /// the new leader is found, and still be considered caught up. Writes the leader accepts
+    /// afterward aren't waited for. Default is `0`.
+    pub fn max_replication_lag(self, max_replication_lag: i64) -> Self {
+        Self {
+            max_replication_lag,
+            ..self
+        }
+    }
+}
+
+/// Progress of [`Client::prepare_node_for_maintenance`].
+#[derive(Clone, Debug)]
+pub enum MaintenanceEvent {
+    /// The node was found alive in the cluster gossip.
+    Started(MemberInfo),
+    PriorityLowered(i32),
+    /// The node was the leader and was asked to resign.
+    Resigned,
+    /// Another node is leader.
+    LeaderElected(MemberInfo),
+    /// Every other alive node is a follower or read-only replica, within the allowed replication
+    /// lag of the leader checkpoint recorded when it was found.
+    FollowersCaughtUp(Vec<MemberInfo>),
+    /// The node can be taken down.
+    Ready,
+}
+
+impl Client {
+    /// Prepares a cluster node, identified by its gossip endpoint, for maintenance. Progress is
+    /// reported as a stream that ends with [`MaintenanceEvent::Ready`], or with the first error.
+    ///
+    /// The node priority isn't restored afterward, call [`Client::set_node_priority`] on
+    /// [`Client::on_node`] once the maintenance is over.
+    ///
+    /// ```no_run
+    /// # async fn patch(client: kurrentdb::operations::Client, node: kurrentdb::Endpoint) -> kurrentdb::Result<()> {
+    /// use futures::TryStreamExt;
+    ///
+    /// let mut progress = client.prepare_node_for_maintenance(&node, &Default::default());
+    ///
+    /// while let Some(event) = progress.try_next().await? {
+    ///     println!("{:?}", event);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn prepare_node_for_maintenance(
+        &self,
+        node: &Endpoint,
+        options: &MaintenanceOptions,
+    ) -> BoxStream<'_, crate::Result<MaintenanceEvent>> {
+        let node = node.clone();
+        let options = options.clone();
+
+        let stream = async_stream::try_stream! {
+            let node = &node;
+            let options = &options;
+            let target = self.on_node(node);
+            let op_options = OperationalOptions {
+                common_operation_options: options.common_operation_options.clone(),
+            };
+
+            let members = self.read_gossip().await?;
+            let member = find_member(&members, node)
+                .filter(|m| m.is_alive)
+                .cloned()
+                .ok_or_else(|| {
+                    crate::Error::IllegalStateError(format!(
+                        "node {:?} isn't an alive cluster member",
+                        node
+                    ))
+                })?;
+
+            let was_leader = member.state == VNodeState::Leader;
+            yield MaintenanceEvent::Started(member);
+
+            target.set_node_priority(options.priority, &op_options).await?;
+            yield MaintenanceEvent::PriorityLowered(options.priority);
+
+            if was_leader {
+                target.resign_node(&op_options).await?;
+                yield MaintenanceEvent::Resigned;
+            }
+
+            let leader = self
+                .wait_for_gossip(options, |members| {
+                    members
+                        .iter()
+                        .find(|m| {
+                            m.is_alive
+                                && m.state == VNodeState::Leader
+                                && m.http_end_point != *node
+                        })
+                        .cloned()
+                })
+                .await?;
+
+            // The leader keeps accepting writes, followers only need to replicate what it had
+            // written by now.
+            let checkpoint = leader
+                .writer_checkpoint
+                .saturating_sub(options.max_replication_lag);
+
+            if was_leader {
+                yield MaintenanceEvent::LeaderElected(leader);
+            }
+
+            let followers = self
+                .wait_for_gossip(options, |members| {
+                    caught_up_followers(members, node, checkpoint)
+                })
+                .await?;
+
+            yield MaintenanceEvent::FollowersCaughtUp(followers);
+            yield MaintenanceEvent::Ready;
+        };
+
+        Box::pin(stream)
+    }
+
+    /// Reads the gossip until `check` accepts it. Read failures are retried until the deadline,
+    /// as the cluster is expected to be briefly unavailable during an election.
+    async fn wait_for_gossip<F, A>(
+        &self,
+        options: &MaintenanceOptions,
+        mut check: F,
+    ) -> crate::Result<A>
+    where
+        F: FnMut(&[MemberInfo]) -> Option<A>,
+    {
+        let deadline = tokio::time::Instant::now() + options.step_timeout;
+
+        loop {
+            match self.read_gossip().await {
+                Ok(members) => {
+                    if let Some(value) = check(&members) {
+                        return Ok(value);
+                    }
+                }
+
+                Err(e) => debug!("Gossip read failed while waiting: {}", e),
+            }
+
+            if tokio::time::Instant::now() + options.poll_interval > deadline {
+                return Err(crate::Error::DeadlineExceeded);
+            }
+
+            tokio::time::sleep(options.poll_interval).await;
+        }
+    }
+}
+
+fn find_member<'a>(members: &'a [MemberInfo], node: &Endpoint) -> Option<&'a MemberInfo> {
+    members.iter().find(|m| m.http_end_point == *node)
+}
+
+/// Returns the alive members other than `node` and the leader, if they all replicated up to the
+/// given writer checkpoint.
+fn caught_up_followers(
+    members: &[MemberInfo],
+    node: &Endpoint,
+    checkpoint: i64,
+) -> Option<Vec<MemberInfo>> {
+    let leader = members
+        .iter()
+        .find(|m| m.is_alive && m.state == VNodeState::Leader)?;
+
+    let followers = members
+        .iter()
+        .filter(|m| m.is_alive && m.http_end_point != *node && m.instance_id != leader.instance_id)
+        .cloned()
+        .collect::<Vec<_>>();
+
+    let caught_up = followers.iter().all(|m| {
+        matches!(m.state, VNodeState::Follower | VNodeState::ReadOnlyReplica)
+            && m.writer_checkpoint >= checkpoint
+    });
+
+    caught_up.then_some(followers)
+}
+
+#[cfg(test)]
+mod maintenance_tests {
+    use super::*;
+
+    fn member(port: u32, state: VNodeState, writer_checkpoint: i64) -> MemberInfo {
+        MemberInfo {
+            instance_id: uuid::Uuid::new_v4(),
+            time_stamp: 0,
+            state,
+            is_alive: true,
+            http_end_point: Endpoint {
+                host: "localhost".to_string(),
+                port,
+            },
+            last_commit_position: 0,
+            writer_checkpoint,
+            chaser_checkpoint: 0,
+            epoch_position: 0,
+            epoch_number: 0,
+            epoch_id: Default::default(),
+            node_priority: 0,
+        }
+    }
+
+    #[test]
+    fn test_caught_up_followers() {
+        let node = Endpoint {
+            host: "localhost".to_string(),
+            port: 2111,
+        };
+
+        let mut members = vec![
+            member(2111, VNodeState::Follower, 0),
+            member(2112, VNodeState::Leader, 100),
+            member(2113, VNodeState::Follower, 90),
+        ];
+
+        assert!(caught_up_followers(&members, &node, 100).is_none());
+        // The leader wrote more since the checkpoint was recorded.
+        assert_eq!(
+            caught_up_followers(&members, &node, 90).map(|f| f.len()),
+            Some(1)
+        );
+
+        members[2].state = VNodeState::CatchingUp;
+        assert!(caught_up_followers(&members, &node, 90).is_none());
+
+        members[2].is_alive = false;
+        assert_eq!(
+            caught_up_followers(&members, &node, 100).map(|f| f.len()),
+            Some(0)
+        );
+    }
+}
@@ -1,4 +1,5 @@
 use eventstore_macros::{options, streaming};
+use futures::Future;
 use futures::stream::TryStreamExt;
 use std::time::SystemTime;
 use std::{collections::HashMap, time::Duration};
@@ -9,9 +10,20 @@ use crate::event_store::generated::users;
 use crate::{ClientSettings, Endpoint};
 
 pub(crate) mod gossip;
+mod maintenance;
+mod scavenge;
+mod stats;
 
 pub use crate::server_features::{Features, ServerInfo, ServerVersion};
 pub use gossip::{MemberInfo, VNodeState};
+pub use maintenance::{MaintenanceEvent, MaintenanceOptions};
+pub use scavenge::{
+    ChunksProgress, IndexProgress, ScavengeEvent, ScavengeHandle, ScavengeOutcome, ScavengeSummary,
+};
+pub use stats::{
+    DiskIo, DriveStats, Es, Gc, LoadAvg, Proc, Queue, QueueDelta, ReadIndex, Statistics,
+    StatsDelta, StatsSample, StatsWatcher, Sys, Tcp, Writer,
+};
 
 #[derive(Clone)]
 pub struct Client {
@@ -39,6 +51,25 @@ impl Default for StatsOptions {
     }
 }
 
+options! {
+    #[derive(Default)]
+    #[streaming]
+    /// Options of [`Client::track_scavenge`].
+    pub struct TrackScavengeOptions {
+        pub(crate) retry: Option<crate::RetryOptions>,
+    }
+}
+
+impl TrackScavengeOptions {
+    /// When a disconnection happens, automatically resubscribe from the last event received.
+    pub fn retry_options(self, options: crate::RetryOptions) -> Self {
+        Self {
+            retry: Some(options),
+            ..self
+        }
+    }
+}
+
 impl StatsOptions {
     pub fn refresh_time(self, value: Duration) -> Self {
         Self {
@@ -71,37 +102,101 @@ impl Client {
     }
 
     pub async fn read_gossip(&self) -> crate::Result<Vec<gossip::MemberInfo>> {
-        let handle = self.inner.current_selected_node().await?;
-
-        // We currently use the http endpoint instead of the gRPC one because at that time
-        // 04-25-2022, the public gRPC endpoint doesn't return all the gossip info like current
-        // epoch and other checkpoints.
-        gossip::http_read(self.inner.connection_settings(), handle)
+        let options = OperationalOptions::default();
+
+        self.inner
+            .execute_idempotent(&options, |handle| async move {
+                // We currently use the http endpoint instead of the gRPC one because at that time
+                // 04-25-2022, the public gRPC endpoint doesn't return all the gossip info like current
+                // epoch and other checkpoints.
+                gossip::http_read(self.inner.connection_settings(), handle)
+                    .await
+                    .map_err(|e| crate::Error::IllegalStateError(e.to_string()))
+            })
             .await
-            .map_err(|e| crate::Error::IllegalStateError(e.to_string()))
     }
 
-    pub async fn stats(&self, options: &StatsOptions) -> crate::Result<Stats> {
-        let handle = self.inner.current_selected_node().await?;
-
-        let req = monitoring::StatsReq {
-            // Using the metadata messes the parsing for no benefit. It only provides value in the UI, it's
-            // better to have all the metrics flattened with only strings.
-            use_metadata: false,
-            refresh_time_period_in_ms: options.refresh_time.as_millis() as u64,
-        };
+    /// Returns a client whose operations all run on the given node, e.g. one from
+    /// [`Client::read_gossip`]. Many admin operations, like [`Client::merge_indexes`] or
+    /// [`Client::start_scavenge`], only affect the node they run on.
+    ///
+    /// It opens its own connection, which never follows the cluster leader, and must be called
+    /// from within a Tokio runtime. TLS identities registered on this client at that time are
+    /// available on the returned one.
+    pub fn on_node(&self, endpoint: &Endpoint) -> Client {
+        Client {
+            inner: self.inner.pinned_to(endpoint),
+        }
+    }
 
-        let req = crate::commands::new_request(self.inner.connection_settings(), options, req);
-        let mut client =
-            monitoring::monitoring_client::MonitoringClient::with_origin(handle.client, handle.uri);
+    /// Runs `action` on every alive node of the cluster, concurrently, and returns each node with
+    /// the outcome of its action.
+    ///
+    /// ```no_run
+    /// # async fn merge(client: kurrentdb::operations::Client) -> kurrentdb::Result<()> {
+    /// let results = client
+    ///     .on_each_node(|node| async move { node.merge_indexes(&Default::default()).await })
+    ///     .await?;
+    ///
+    /// for (member, result) in results {
+    ///     println!("{:?}: {:?}", member.http_end_point, result);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn on_each_node<F, Fut, A>(
+        &self,
+        mut action: F,
+    ) -> crate::Result<Vec<(gossip::MemberInfo, crate::Result<A>)>>
+    where
+        F: FnMut(Client) -> Fut,
+        Fut: Future<Output = crate::Result<A>>,
+    {
+        let members = self
+            .read_gossip()
+            .await?
+            .into_iter()
+            .filter(|member| member.is_alive)
+            .collect::<Vec<_>>();
+
+        let outcomes = futures::future::join_all(
+            members
+                .iter()
+                .map(|member| action(self.on_node(&member.http_end_point))),
+        )
+        .await;
+
+        Ok(members.into_iter().zip(outcomes).collect())
+    }
 
-        let inner = client
-            .stats(req)
+    pub async fn stats(&self, options: &StatsOptions) -> crate::Result<Stats> {
+        self.inner
+            .execute_idempotent(options, |handle| {
+                let req = monitoring::StatsReq {
+                    // Using the metadata messes the parsing for no benefit. It only provides value in the UI, it's
+                    // better to have all the metrics flattened with only strings.
+                    use_metadata: false,
+                    refresh_time_period_in_ms: options.refresh_time.as_millis() as u64,
+                };
+
+                let req =
+                    crate::commands::new_request(self.inner.connection_settings(), options, req);
+
+                async move {
+                    let mut client = monitoring::monitoring_client::MonitoringClient::with_origin(
+                        handle.client,
+                        handle.uri,
+                    );
+
+                    let inner = client.stats(req).await?.into_inner();
+
+                    Ok(Stats {
+                        inner,
+                        node: handle.endpoint,
+                    })
+                }
+            })
             .await
-            .map_err(crate::Error::from_grpc)?
-            .into_inner();
-
-        Ok(Stats { inner })
     }
 
     pub async fn start_scavenge(
@@ -110,8 +205,6 @@ impl Client {
         start_from_chunk: usize,
         options: &OperationalOptions,
     ) -> crate::Result<ScavengeResult> {
-        let handle = self.inner.current_selected_node().await?;
-
         let req = operations::StartScavengeReq {
             options: Some(operations::start_scavenge_req::Options {
                 thread_count: thread_count as i32,
@@ -119,25 +212,62 @@ impl Client {
             }),
         };
 
-        let req = crate::commands::new_request(self.inner.connection_settings(), options, req);
+        self.inner
+            .execute_once(options, |handle| {
+                let req =
+                    crate::commands::new_request(self.inner.connection_settings(), options, req);
+
+                async move {
+                    let mut client = operations::operations_client::OperationsClient::with_origin(
+                        handle.client,
+                        handle.uri,
+                    );
 
-        let mut client =
-            operations::operations_client::OperationsClient::with_origin(handle.client, handle.uri);
+                    let resp = client.start_scavenge(req).await?.into_inner();
 
-        let resp = client
-            .start_scavenge(req)
+                    Ok(ScavengeResult::from_wire(resp))
+                }
+            })
             .await
-            .map_err(crate::Error::from_grpc)?
-            .into_inner();
+    }
 
-        let status = match resp.scavenge_result() {
-            operations::scavenge_resp::ScavengeResult::Started => ScavengeStatus::Started,
-            operations::scavenge_resp::ScavengeResult::InProgress => ScavengeStatus::InProgress,
-            operations::scavenge_resp::ScavengeResult::Stopped => ScavengeStatus::Stopped,
+    /// Follows the progress of a scavenge started with [`Client::start_scavenge`], from its
+    /// first logged step. Works for running and already completed scavenges alike.
+    ///
+    /// ```no_run
+    /// # async fn nightly(client: kurrentdb::operations::Client) -> kurrentdb::Result<()> {
+    /// let scavenge = client.start_scavenge(1, 0, &Default::default()).await?;
+    /// let summary = client
+    ///     .track_scavenge(scavenge.id(), &Default::default())
+    ///     .await_completion()
+    ///     .await?;
+    ///
+    /// println!("{:?}: {} bytes reclaimed", summary.outcome, summary.space_saved);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn track_scavenge(
+        &self,
+        scavenge_id: impl AsRef<str>,
+        options: &TrackScavengeOptions,
+    ) -> ScavengeHandle {
+        let id = scavenge_id.as_ref().to_string();
+        let mut subscribe_options = crate::SubscribeToStreamOptions {
+            common_operation_options: options.common_operation_options.clone(),
+            ..crate::SubscribeToStreamOptions::default().start_from(crate::StreamPosition::Start)
         };
-        let id = resp.scavenge_id;
 
-        Ok(ScavengeResult { id, status })
+        if let Some(retry) = options.retry {
+            subscribe_options = subscribe_options.retry_options(retry);
+        }
+
+        let inner = crate::commands::subscribe_to_stream(
+            self.inner.clone(),
+            format!("$scavenges-{}", id),
+            &subscribe_options,
+        );
+
+        ScavengeHandle::new(id, inner)
     }
 
     pub async fn stop_scavenge(
@@ -145,110 +275,143 @@ impl Client {
         scavenge_id: impl AsRef<str>,
         options: &OperationalOptions,
     ) -> crate::Result<ScavengeResult> {
-        let handle = self.inner.current_selected_node().await?;
-
         let req = operations::StopScavengeReq {
             options: Some(operations::stop_scavenge_req::Options {
                 scavenge_id: scavenge_id.as_ref().to_string(),
             }),
         };
 
-        let req = crate::commands::new_request(self.inner.connection_settings(), options, req);
+        self.inner
+            .execute_idempotent(options, |handle| {
+                let req = crate::commands::new_request(
+                    self.inner.connection_settings(),
+                    options,
+                    req.clone(),
+                );
 
-        let mut client =
-            operations::operations_client::OperationsClient::with_origin(handle.client, handle.uri);
+                async move {
+                    let mut client = operations::operations_client::OperationsClient::with_origin(
+                        handle.client,
+                        handle.uri,
+                    );
 
-        let resp = client
-            .stop_scavenge(req)
-            .await
-            .map_err(crate::Error::from_grpc)?
-            .into_inner();
+                    let resp = client.stop_scavenge(req).await?.into_inner();
 
-        let status = match resp.scavenge_result() {
-            operations::scavenge_resp::ScavengeResult::Started => ScavengeStatus::Started,
-            operations::scavenge_resp::ScavengeResult::InProgress => ScavengeStatus::InProgress,
-            operations::scavenge_resp::ScavengeResult::Stopped => ScavengeStatus::Stopped,
-        };
-        let id = resp.scavenge_id;
-
-        Ok(ScavengeResult { id, status })
+                    Ok(ScavengeResult::from_wire(resp))
+                }
+            })
+            .await
     }
 
     pub async fn shutdown(&self, options: &OperationalOptions) -> crate::Result<()> {
-        let handle = self.inner.current_selected_node().await?;
+        self.inner
+            .execute_once(options, |handle| {
+                let req =
+                    crate::commands::new_request(self.inner.connection_settings(), options, ());
+
+                async move {
+                    let mut client = operations::operations_client::OperationsClient::with_origin(
+                        handle.client,
+                        handle.uri,
+                    );
 
-        let mut client =
-            operations::operations_client::OperationsClient::with_origin(handle.client, handle.uri);
-        let req = crate::commands::new_request(self.inner.connection_settings(), options, ());
-        client
-            .shutdown(req)
+                    client.shutdown(req).await?;
+
+                    Ok(())
+                }
+            })
             .await
-            .map_err(crate::Error::from_grpc)
-            .map(|_| ())
     }
 
     pub async fn merge_indexes(&self, options: &OperationalOptions) -> crate::Result<()> {
-        let handle = self.inner.current_selected_node().await?;
-        let mut client =
-            operations::operations_client::OperationsClient::with_origin(handle.client, handle.uri);
-        let req = crate::commands::new_request(self.inner.connection_settings(), options, ());
+        self.inner
+            .execute_once(options, |handle| {
+                let req =
+                    crate::commands::new_request(self.inner.connection_settings(), options, ());
+
+                async move {
+                    let mut client = operations::operations_client::OperationsClient::with_origin(
+                        handle.client,
+                        handle.uri,
+                    );
+
+                    client.merge_indexes(req).await?;
 
-        client
-            .merge_indexes(req)
+                    Ok(())
+                }
+            })
             .await
-            .map_err(crate::Error::from_grpc)
-            .map(|_| ())
     }
 
     pub async fn resign_node(&self, options: &OperationalOptions) -> crate::Result<()> {
-        let handle = self.inner.current_selected_node().await?;
-        let mut client =
-            operations::operations_client::OperationsClient::with_origin(handle.client, handle.uri);
-        let req = crate::commands::new_request(self.inner.connection_settings(), options, ());
+        self.inner
+            .execute_once(options, |handle| {
+                let req =
+                    crate::commands::new_request(self.inner.connection_settings(), options, ());
+
+                async move {
+                    let mut client = operations::operations_client::OperationsClient::with_origin(
+                        handle.client,
+                        handle.uri,
+                    );
 
-        client
-            .resign_node(req)
+                    client.resign_node(req).await?;
+
+                    Ok(())
+                }
+            })
             .await
-            .map_err(crate::Error::from_grpc)
-            .map(|_| ())
     }
 
+    /// Sets the priority of the node in leader elections. Nodes with a lower priority are less
+    /// likely to be elected leader. The server default is `0` and negative values are accepted.
     pub async fn set_node_priority(
         &self,
-        priority: usize,
+        priority: i32,
         options: &OperationalOptions,
     ) -> crate::Result<()> {
-        let handle = self.inner.current_selected_node().await?;
-        let mut client =
-            operations::operations_client::OperationsClient::with_origin(handle.client, handle.uri);
+        let req = operations::SetNodePriorityReq { priority };
 
-        let req = operations::SetNodePriorityReq {
-            priority: priority as i32,
-        };
+        self.inner
+            .execute_once(options, |handle| {
+                let req =
+                    crate::commands::new_request(self.inner.connection_settings(), options, req);
+
+                async move {
+                    let mut client = operations::operations_client::OperationsClient::with_origin(
+                        handle.client,
+                        handle.uri,
+                    );
 
-        let req = crate::commands::new_request(self.inner.connection_settings(), options, req);
+                    client.set_node_priority(req).await?;
 
-        client
-            .set_node_priority(req)
+                    Ok(())
+                }
+            })
             .await
-            .map_err(crate::Error::from_grpc)
-            .map(|_| ())
     }
 
     pub async fn restart_persistent_subscriptions(
         &self,
         options: &OperationalOptions,
     ) -> crate::Result<()> {
-        let handle = self.inner.current_selected_node().await?;
-        let mut client =
-            operations::operations_client::OperationsClient::with_origin(handle.client, handle.uri);
-        let req = crate::commands::new_request(self.inner.connection_settings(), options, ());
+        self.inner
+            .execute_once(options, |handle| {
+                let req =
+                    crate::commands::new_request(self.inner.connection_settings(), options, ());
+
+                async move {
+                    let mut client = operations::operations_client::OperationsClient::with_origin(
+                        handle.client,
+                        handle.uri,
+                    );
 
-        client
-            .restart_persistent_subscriptions(req)
+                    client.restart_persistent_subscriptions(req).await?;
+
+                    Ok(())
+                }
+            })
             .await
-            .map_err(crate::Error::from_grpc)
-            .map(|_| ())
     }
 
     pub async fn create_user(
@@ -259,9 +422,6 @@ impl Client {
         groups: Vec<String>,
         options: &OperationalOptions,
     ) -> crate::Result<()> {
-        let handle = self.inner.current_selected_node().await?;
-        let mut client = users::users_client::UsersClient::with_origin(handle.client, handle.uri);
-
         let req = users::CreateReq {
             options: Some(users::create_req::Options {
                 login_name: login.as_ref().to_string(),
@@ -271,13 +431,24 @@ impl Client {
             }),
         };
 
-        let req = crate::commands::new_request(self.inner.connection_settings(), options, req);
+        self.inner
+            .execute(options, |handle| {
+                let req = crate::commands::new_request(
+                    self.inner.connection_settings(),
+                    options,
+                    req.clone(),
+                );
+
+                async move {
+                    let mut client =
+                        users::users_client::UsersClient::with_origin(handle.client, handle.uri);
 
-        client
-            .create(req)
+                    client.create(req).await?;
+
+                    Ok(())
+                }
+            })
             .await
-            .map_err(crate::Error::from_grpc)
-            .map(|_| ())
     }
 
     pub async fn update_user(
@@ -288,9 +459,6 @@ impl Client {
         groups: Vec<String>,
         options: &OperationalOptions,
     ) -> crate::Result<()> {
-        let handle = self.inner.current_selected_node().await?;
-        let mut client = users::users_client::UsersClient::with_origin(handle.client, handle.uri);
-
         let req = users::UpdateReq {
             options: Some(users::update_req::Options {
                 login_name: login.as_ref().to_string(),
@@ -300,13 +468,24 @@ impl Client {
             }),
         };
 
-        let req = crate::commands::new_request(self.inner.connection_settings(), options, req);
+        self.inner
+            .execute_idempotent(options, |handle| {
+                let req = crate::commands::new_request(
+                    self.inner.connection_settings(),
+                    options,
+                    req.clone(),
+                );
+
+                async move {
+                    let mut client =
+                        users::users_client::UsersClient::with_origin(handle.client, handle.uri);
 
-        client
-            .update(req)
+                    client.update(req).await?;
+
+                    Ok(())
+                }
+            })
             .await
-            .map_err(crate::Error::from_grpc)
-            .map(|_| ())
     }
 
     pub async fn delete_user(
@@ -314,22 +493,30 @@ impl Client {
         login: impl AsRef<str>,
         options: &OperationalOptions,
     ) -> crate::Result<()> {
-        let handle = self.inner.current_selected_node().await?;
-        let mut client = users::users_client::UsersClient::with_origin(handle.client, handle.uri);
-
         let req = users::DeleteReq {
             options: Some(users::delete_req::Options {
                 login_name: login.as_ref().to_string(),
             }),
         };
 
-        let req = crate::commands::new_request(self.inner.connection_settings(), options, req);
+        self.inner
+            .execute(options, |handle| {
+                let req = crate::commands::new_request(
+                    self.inner.connection_settings(),
+                    options,
+                    req.clone(),
+                );
+
+                async move {
+                    let mut client =
+                        users::users_client::UsersClient::with_origin(handle.client, handle.uri);
 
-        client
-            .delete(req)
+                    client.delete(req).await?;
+
+                    Ok(())
+                }
+            })
             .await
-            .map_err(crate::Error::from_grpc)
-            .map(|_| ())
     }
 
     pub async fn enable_user(
@@ -337,22 +524,30 @@ impl Client {
         login: impl AsRef<str>,
         options: &OperationalOptions,
     ) -> crate::Result<()> {
-        let handle = self.inner.current_selected_node().await?;
-        let mut client = users::users_client::UsersClient::with_origin(handle.client, handle.uri);
-
         let req = users::EnableReq {
             options: Some(users::enable_req::Options {
                 login_name: login.as_ref().to_string(),
             }),
         };
 
-        let req = crate::commands::new_request(self.inner.connection_settings(), options, req);
+        self.inner
+            .execute_idempotent(options, |handle| {
+                let req = crate::commands::new_request(
+                    self.inner.connection_settings(),
+                    options,
+                    req.clone(),
+                );
+
+                async move {
+                    let mut client =
+                        users::users_client::UsersClient::with_origin(handle.client, handle.uri);
+
+                    client.enable(req).await?;
 
-        client
-            .enable(req)
+                    Ok(())
+                }
+            })
             .await
-            .map_err(crate::Error::from_grpc)
-            .map(|_| ())
     }
 
     pub async fn disable_user(
@@ -360,22 +555,30 @@ impl Client {
         login: impl AsRef<str>,
         options: &OperationalOptions,
     ) -> crate::Result<()> {
-        let handle = self.inner.current_selected_node().await?;
-        let mut client = users::users_client::UsersClient::with_origin(handle.client, handle.uri);
-
         let req = users::DisableReq {
             options: Some(users::disable_req::Options {
                 login_name: login.as_ref().to_string(),
             }),
         };
 
-        let req = crate::commands::new_request(self.inner.connection_settings(), options, req);
+        self.inner
+            .execute_idempotent(options, |handle| {
+                let req = crate::commands::new_request(
+                    self.inner.connection_settings(),
+                    options,
+                    req.clone(),
+                );
+
+                async move {
+                    let mut client =
+                        users::users_client::UsersClient::with_origin(handle.client, handle.uri);
+
+                    client.disable(req).await?;
 
-        client
-            .disable(req)
+                    Ok(())
+                }
+            })
             .await
-            .map_err(crate::Error::from_grpc)
-            .map(|_| ())
     }
 
     pub async fn user_details(
@@ -383,24 +586,124 @@ impl Client {
         login: impl AsRef<str>,
         options: &OperationalOptions,
     ) -> crate::Result<UserDetailsStream> {
-        let handle = self.inner.current_selected_node().await?;
-        let mut client = users::users_client::UsersClient::with_origin(handle.client, handle.uri);
-
         let req = users::DetailsReq {
             options: Some(users::details_req::Options {
                 login_name: login.as_ref().to_string(),
             }),
         };
 
-        let req = crate::commands::new_request(self.inner.connection_settings(), options, req);
+        self.inner
+            .execute_idempotent(options, |handle| {
+                let req = crate::commands::new_request(
+                    self.inner.connection_settings(),
+                    options,
+                    req.clone(),
+                );
+
+                async move {
+                    let mut client =
+                        users::users_client::UsersClient::with_origin(handle.client, handle.uri);
+
+                    let inner = client.details(req).await?.into_inner();
 
-        let inner = client
-            .details(req)
+                    Ok(UserDetailsStream { inner })
+                }
+            })
+            .await
+    }
+
+    /// Streams the details of every user, disabled ones included.
+    pub async fn list_users(
+        &self,
+        options: &OperationalOptions,
+    ) -> crate::Result<UserDetailsStream> {
+        self.inner
+            .execute_idempotent(options, |handle| {
+                let req = crate::commands::new_request(
+                    self.inner.connection_settings(),
+                    options,
+                    users::DetailsReq { options: None },
+                );
+
+                async move {
+                    let mut client =
+                        users::users_client::UsersClient::with_origin(handle.client, handle.uri);
+
+                    let inner = client.details(req).await?.into_inner();
+
+                    Ok(UserDetailsStream { inner })
+                }
+            })
             .await
-            .map_err(crate::Error::from_grpc)?
-            .into_inner();
+    }
+
+    /// Adds a user to a group, e.g. [`SystemRole::Admins`]. Does nothing if the user already
+    /// belongs to it.
+    ///
+    /// The groups are read then written back with [`Client::update_user`], concurrent changes to
+    /// the same user can be lost.
+    pub async fn add_user_to_group(
+        &self,
+        login: impl AsRef<str>,
+        group: impl AsRef<str>,
+        options: &OperationalOptions,
+    ) -> crate::Result<()> {
+        let group = group.as_ref();
+
+        self.update_user_groups(login.as_ref(), options, |groups| {
+            if groups.iter().any(|g| g == group) {
+                return false;
+            }
+
+            groups.push(group.to_string());
+            true
+        })
+        .await
+    }
+
+    /// Removes a user from a group. Does nothing if the user doesn't belong to it. Same caveat as
+    /// [`Client::add_user_to_group`].
+    pub async fn remove_user_from_group(
+        &self,
+        login: impl AsRef<str>,
+        group: impl AsRef<str>,
+        options: &OperationalOptions,
+    ) -> crate::Result<()> {
+        let group = group.as_ref();
+
+        self.update_user_groups(login.as_ref(), options, |groups| {
+            let count = groups.len();
+            groups.retain(|g| g != group);
+
+            groups.len() != count
+        })
+        .await
+    }
+
+    async fn update_user_groups<F>(
+        &self,
+        login: &str,
+        options: &OperationalOptions,
+        change: F,
+    ) -> crate::Result<()>
+    where
+        F: FnOnce(&mut Vec<String>) -> bool,
+    {
+        let mut details = self
+            .user_details(login, options)
+            .await?
+            .next()
+            .await?
+            .ok_or(crate::Error::ResourceNotFound)?;
+
+        if !change(&mut details.groups) {
+            return Ok(());
+        }
 
-        Ok(UserDetailsStream { inner })
+        // The server ignores the password on update, it's only changed through
+        // `change_user_password` and `reset_user_password`.
+        self.update_user(login, "", details.full_name, details.groups, options)
+            .await
     }
 
     pub async fn change_user_password(
@@ -410,9 +713,6 @@ impl Client {
         new_password: impl AsRef<str>,
         options: &OperationalOptions,
     ) -> crate::Result<()> {
-        let handle = self.inner.current_selected_node().await?;
-        let mut client = users::users_client::UsersClient::with_origin(handle.client, handle.uri);
-
         let req = users::ChangePasswordReq {
             options: Some(users::change_password_req::Options {
                 login_name: login.as_ref().to_string(),
@@ -421,13 +721,24 @@ impl Client {
             }),
         };
 
-        let req = crate::commands::new_request(self.inner.connection_settings(), options, req);
+        self.inner
+            .execute(options, |handle| {
+                let req = crate::commands::new_request(
+                    self.inner.connection_settings(),
+                    options,
+                    req.clone(),
+                );
 
-        client
-            .change_password(req)
+                async move {
+                    let mut client =
+                        users::users_client::UsersClient::with_origin(handle.client, handle.uri);
+
+                    client.change_password(req).await?;
+
+                    Ok(())
+                }
+            })
             .await
-            .map_err(crate::Error::from_grpc)
-            .map(|_| ())
     }
 
     pub async fn reset_user_password(
@@ -436,9 +747,6 @@ impl Client {
         new_password: impl AsRef<str>,
         options: &OperationalOptions,
     ) -> crate::Result<()> {
-        let handle = self.inner.current_selected_node().await?;
-        let mut client = users::users_client::UsersClient::with_origin(handle.client, handle.uri);
-
         let req = users::ResetPasswordReq {
             options: Some(users::reset_password_req::Options {
                 login_name: login.as_ref().to_string(),
@@ -446,13 +754,24 @@ impl Client {
             }),
         };
 
-        let req = crate::commands::new_request(self.inner.connection_settings(), options, req);
+        self.inner
+            .execute_idempotent(options, |handle| {
+                let req = crate::commands::new_request(
+                    self.inner.connection_settings(),
+                    options,
+                    req.clone(),
+                );
 
-        client
-            .reset_password(req)
+                async move {
+                    let mut client =
+                        users::users_client::UsersClient::with_origin(handle.client, handle.uri);
+
+                    client.reset_password(req).await?;
+
+                    Ok(())
+                }
+            })
             .await
-            .map_err(crate::Error::from_grpc)
-            .map(|_| ())
     }
 }
 
@@ -467,6 +786,7 @@ pub struct RawStatistics(pub HashMap<String, String>);
 
 pub struct Stats {
     inner: tonic::Streaming<monitoring::StatsResp>,
+    node: Endpoint,
 }
 
 impl From<HashMap<String, String>> for RawStatistics {
@@ -476,6 +796,11 @@ impl From<HashMap<String, String>> for RawStatistics {
 }
 
 impl Stats {
+    /// The node sending the statistics.
+    pub fn node(&self) -> &Endpoint {
+        &self.node
+    }
+
     pub async fn next(&mut self) -> crate::Result<RawStatistics> {
         let result = self
             .inner
@@ -533,6 +858,42 @@ pub struct UserDetails {
     pub last_updated: Option<SystemTime>,
 }
 
+/// Groups granting the server built-in permissions.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SystemRole {
+    /// `$admins`, full access.
+    Admins,
+    /// `$ops`, operational tasks like scavenges and node management.
+    Operations,
+}
+
+impl SystemRole {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SystemRole::Admins => "$admins",
+            SystemRole::Operations => "$ops",
+        }
+    }
+}
+
+impl AsRef<str> for SystemRole {
+    fn as_ref(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl std::fmt::Display for SystemRole {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl UserDetails {
+    pub fn is_in_group(&self, group: impl AsRef<str>) -> bool {
+        self.groups.iter().any(|g| g == group.as_ref())
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct ScavengeResult {
     id: String,
@@ -549,6 +910,21 @@ impl ScavengeResult {
     }
 }
 
+impl ScavengeResult {
+    fn from_wire(resp: operations::ScavengeResp) -> Self {
+        let status = match resp.scavenge_result() {
+            operations::scavenge_resp::ScavengeResult::Started => ScavengeStatus::Started,
+            operations::scavenge_resp::ScavengeResult::InProgress => ScavengeStatus::InProgress,
+            operations::scavenge_resp::ScavengeResult::Stopped => ScavengeStatus::Stopped,
+        };
+
+        ScavengeResult {
+            id: resp.scavenge_id,
+            status,
+        }
+    }
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum ScavengeStatus {
     Started,
@@ -0,0 +1,369 @@
+//! Progress of a scavenge, as the server logs it in the `$scavenges-<id>` stream.
+use std::time::Duration;
+
+use serde::Deserialize;
+
+use crate::{RecordedEvent, Subscription};
+
+const STARTED: &str = "$scavengeStarted";
+const CHUNKS_COMPLETED: &str = "$scavengeChunksCompleted";
+const MERGE_COMPLETED: &str = "$scavengeMergeCompleted";
+const INDEX_COMPLETED: &str = "$scavengeIndexCompleted";
+const COMPLETED: &str = "$scavengeCompleted";
+
+/// Step of a scavenge, as logged by the server.
+///
+/// The server only logs steps once they are done, there is no event for a chunk being started.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ScavengeEvent {
+    Started {
+        node_endpoint: Option<String>,
+    },
+    /// A range of chunks was scavenged.
+    ChunksCompleted(ChunksProgress),
+    /// A range of chunks was merged into a single one.
+    ChunksMerged(ChunksProgress),
+    /// An index table was scavenged.
+    IndexCompleted(IndexProgress),
+    Completed(ScavengeSummary),
+}
+
+/// Outcome of a chunk scavenge or merge step.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ChunksProgress {
+    pub chunk_start_number: i64,
+    pub chunk_end_number: i64,
+    pub time_taken: Option<Duration>,
+    /// `false` when the chunks were left untouched, either because there was nothing to reclaim or
+    /// because of an error.
+    pub applied: bool,
+    pub space_saved: i64,
+    pub error: Option<String>,
+}
+
+/// Outcome of an index table scavenge step.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct IndexProgress {
+    pub level: i32,
+    pub index: i32,
+    pub time_taken: Option<Duration>,
+    pub scavenged: bool,
+    pub entries_deleted: i64,
+    pub space_saved: i64,
+    pub error: Option<String>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ScavengeOutcome {
+    Success,
+    Stopped,
+    Failed,
+    Unknown,
+}
+
+/// How a scavenge ended. See [`ScavengeHandle::await_completion`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ScavengeSummary {
+    pub scavenge_id: String,
+    pub outcome: ScavengeOutcome,
+    pub error: Option<String>,
+    pub time_taken: Option<Duration>,
+    /// Total space reclaimed, in bytes, as reported by the server.
+    pub space_saved: i64,
+    pub max_chunk_scavenged: Option<i64>,
+    /// Chunk ranges scavenged or merged, counted from the events seen by the handle.
+    pub chunks_completed: usize,
+    /// Errors of individual steps, collected from the events seen by the handle.
+    pub step_errors: Vec<String>,
+}
+
+impl ScavengeSummary {
+    pub fn is_success(&self) -> bool {
+        self.outcome == ScavengeOutcome::Success
+    }
+}
+
+/// Follows the progress of a scavenge. See [`crate::operations::Client::track_scavenge`].
+pub struct ScavengeHandle {
+    id: String,
+    inner: Subscription,
+    chunks_completed: usize,
+    step_errors: Vec<String>,
+    completed: bool,
+}
+
+impl ScavengeHandle {
+    pub(crate) fn new(id: String, inner: Subscription) -> Self {
+        Self {
+            id,
+            inner,
+            chunks_completed: 0,
+            step_errors: Vec::new(),
+            completed: false,
+        }
+    }
+
+    pub fn id(&self) -> &str {
+        self.id.as_str()
+    }
+
+    /// Waits for the next step of the scavenge. Returns `None` once the scavenge has completed.
+    pub async fn next(&mut self) -> crate::Result<Option<ScavengeEvent>> {
+        while !self.completed {
+            let event = self.inner.next().await?;
+            let event = match parse_event(event.get_original_event())? {
+                Some(event) => event,
+                None => continue,
+            };
+
+            return Ok(Some(self.track(event)));
+        }
+
+        Ok(None)
+    }
+
+    /// Waits for the scavenge to end and summarizes it. Events already consumed with
+    /// [`ScavengeHandle::next`] are accounted for.
+    pub async fn await_completion(mut self) -> crate::Result<ScavengeSummary> {
+        while let Some(event) = self.next().await? {
+            if let ScavengeEvent::Completed(summary) = event {
+                return Ok(summary);
+            }
+        }
+
+        Err(crate::Error::IllegalStateError(format!(
+            "scavenge '{}' already completed",
+            self.id
+        )))
+    }
+
+    fn track(&mut self, event: ScavengeEvent) -> ScavengeEvent {
+        match event {
+            ScavengeEvent::ChunksCompleted(ref progress)
+            | ScavengeEvent::ChunksMerged(ref progress) => {
+                self.chunks_completed += 1;
+                self.step_errors.extend(progress.error.iter().cloned());
+                event
+            }
+
+            ScavengeEvent::IndexCompleted(ref progress) => {
+                self.step_errors.extend(progress.error.iter().cloned());
+                event
+            }
+
+            ScavengeEvent::Completed(summary) => {
+                self.completed = true;
+
+                ScavengeEvent::Completed(ScavengeSummary {
+                    chunks_completed: self.chunks_completed,
+                    step_errors: self.step_errors.clone(),
+                    ..summary
+                })
+            }
+
+            event => event,
+        }
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct StartedEvent {
+    #[serde(default)]
+    node_endpoint: Option<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ChunksEvent {
+    chunk_start_number: i64,
+    chunk_end_number: i64,
+    #[serde(default)]
+    time_taken: Option<String>,
+    #[serde(default, alias = "wasMerged")]
+    was_scavenged: bool,
+    #[serde(default)]
+    space_saved: i64,
+    #[serde(default)]
+    error_message: Option<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct IndexEvent {
+    #[serde(default)]
+    level: i32,
+    #[serde(default)]
+    index: i32,
+    #[serde(default)]
+    time_taken: Option<String>,
+    #[serde(default)]
+    was_scavenged: bool,
+    #[serde(default)]
+    entries_deleted: i64,
+    #[serde(default)]
+    space_saved: i64,
+    #[serde(default)]
+    error_message: Option<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct CompletedEvent {
+    scavenge_id: String,
+    #[serde(default)]
+    result: serde_json::Value,
+    #[serde(default)]
+    error: Option<String>,
+    #[serde(default)]
+    time_taken: Option<String>,
+    #[serde(default)]
+    space_saved: i64,
+    #[serde(default)]
+    max_chunk_scavenged: Option<i64>,
+}
+
+fn parse_event(event: &RecordedEvent) -> crate::Result<Option<ScavengeEvent>> {
+    let parsed = match event.event_type.as_str() {
+        STARTED => event
+            .as_json::<StartedEvent>()
+            .map(|e| ScavengeEvent::Started {
+                node_endpoint: e.node_endpoint,
+            }),
+
+        CHUNKS_COMPLETED => event
+            .as_json::<ChunksEvent>()
+            .map(|e| ScavengeEvent::ChunksCompleted(e.into())),
+
+        MERGE_COMPLETED => event
+            .as_json::<ChunksEvent>()
+            .map(|e| ScavengeEvent::ChunksMerged(e.into())),
+
+        INDEX_COMPLETED => event.as_json::<IndexEvent>().map(|e| {
+            ScavengeEvent::IndexCompleted(IndexProgress {
+                level: e.level,
+                index: e.index,
+                time_taken: e.time_taken.as_deref().and_then(parse_time_span),
+                scavenged: e.was_scavenged,
+                entries_deleted: e.entries_deleted,
+                space_saved: e.space_saved,
+                error: non_empty(e.error_message),
+            })
+        }),
+
+        COMPLETED => event.as_json::<CompletedEvent>().map(|e| {
+            ScavengeEvent::Completed(ScavengeSummary {
+                scavenge_id: e.scavenge_id,
+                outcome: parse_outcome(&e.result),
+                error: non_empty(e.error),
+                time_taken: e.time_taken.as_deref().and_then(parse_time_span),
+                space_saved: e.space_saved,
+                max_chunk_scavenged: e.max_chunk_scavenged,
+                chunks_completed: 0,
+                step_errors: Vec::new(),
+            })
+        }),
+
+        _ => return Ok(None),
+    };
+
+    parsed.map(Some).map_err(|e| {
+        crate::Error::InternalParsingError(format!("invalid '{}' event: {}", event.event_type, e))
+    })
+}
+
+impl From<ChunksEvent> for ChunksProgress {
+    fn from(e: ChunksEvent) -> Self {
+        Self {
+            chunk_start_number: e.chunk_start_number,
+            chunk_end_number: e.chunk_end_number,
+            time_taken: e.time_taken.as_deref().and_then(parse_time_span),
+            applied: e.was_scavenged,
+            space_saved: e.space_saved,
+            error: non_empty(e.error_message),
+        }
+    }
+}
+
+fn non_empty(value: Option<String>) -> Option<String> {
+    value.filter(|v| !v.is_empty())
+}
+
+fn parse_outcome(value: &serde_json::Value) -> ScavengeOutcome {
+    let Some(value) = value.as_str() else {
+        return ScavengeOutcome::Unknown;
+    };
+
+    match value.to_lowercase().as_str() {
+        "success" => ScavengeOutcome::Success,
+        "stopped" => ScavengeOutcome::Stopped,
+        "failed" | "errored" => ScavengeOutcome::Failed,
+        _ => ScavengeOutcome::Unknown,
+    }
+}
+
+/// Parses a .NET `TimeSpan`, formatted as `[-][d.]hh:mm:ss[.fffffff]`.
+fn parse_time_span(value: &str) -> Option<Duration> {
+    let value = value.trim();
+    if value.starts_with('-') {
+        return None;
+    }
+
+    let mut parts = value.split(':');
+    let first = parts.next()?;
+    let (days, hours) = match first.split_once('.') {
+        Some((days, hours)) => (days.parse::<u64>().ok()?, hours.parse::<u64>().ok()?),
+        None => (0, first.parse::<u64>().ok()?),
+    };
+
+    let minutes = parts.next()?.parse::<u64>().ok()?;
+    let seconds = parts.next()?.parse::<f64>().ok()?;
+
+    if parts.next().is_some() || !seconds.is_finite() || seconds < 0.0 {
+        return None;
+    }
+
+    Some(
+        Duration::from_secs((days * 24 + hours) * 3_600 + minutes * 60)
+            + Duration::from_secs_f64(seconds),
+    )
+}
+
+#[cfg(test)]
+mod scavenge_tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_time_span() {
+        assert_eq!(
+            parse_time_span("00:01:02.5000000"),
+            Some(Duration::from_millis(62_500))
+        );
+        assert_eq!(
+            parse_time_span("1.02:00:00"),
+            Some(Duration::from_secs(26 * 3_600))
+        );
+        assert_eq!(parse_time_span("-00:00:01"), None);
+        assert_eq!(parse_time_span("garbage"), None);
+    }
+
+    #[test]
+    fn test_parse_outcome() {
+        assert_eq!(
+            parse_outcome(&serde_json::json!("Success")),
+            ScavengeOutcome::Success
+        );
+        assert_eq!(
+            parse_outcome(&serde_json::json!("Stopped")),
+            ScavengeOutcome::Stopped
+        );
+        assert_eq!(
+            parse_outcome(&serde_json::json!("Errored")),
+            ScavengeOutcome::Failed
+        );
+        assert_eq!(
+            parse_outcome(&serde_json::Value::Null),
+            ScavengeOutcome::Unknown
+        );
+    }
+}
@@ -0,0 +1,538 @@
+//! Typed view of the server statistics streamed by [`Client::stats`].
+//!
+//! The server reports its statistics as a flat map of dash-separated keys, like `proc-gc-gen0Size`,
+//! whose set changes between server versions. [`Statistics`] nests them back and deserializes what
+//! it knows: unknown keys are ignored and missing or malformed values fall back to their default.
+use std::collections::{BTreeMap, HashMap};
+use std::str::FromStr;
+use std::time::{Duration, Instant};
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Deserializer};
+use serde_json::{Map, Value};
+
+use super::{Client, RawStatistics, Stats, StatsOptions};
+
+/// Prefixes of keys embedding a free-form name, e.g. `es-queue-Worker #1-length`.
+const NAMED_PREFIXES: [&str; 2] = ["es-queue-", "sys-drive-"];
+
+#[derive(Debug, Default, Clone, Deserialize)]
+#[serde(default)]
+pub struct Statistics {
+    pub proc: Proc,
+    pub sys: Sys,
+    pub es: Es,
+    /// Every statistic as sent by the server, including the ones not modeled here.
+    #[serde(skip)]
+    pub raw: HashMap<String, String>,
+}
+
+#[derive(Debug, Default, Clone, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct Proc {
+    #[serde(deserialize_with = "lenient")]
+    pub id: i64,
+    #[serde(deserialize_with = "lenient_option")]
+    pub start_time: Option<DateTime<Utc>>,
+    #[serde(deserialize_with = "lenient")]
+    pub mem: i64,
+    #[serde(deserialize_with = "lenient")]
+    pub cpu: f64,
+    #[serde(deserialize_with = "lenient")]
+    pub threads_count: i64,
+    #[serde(deserialize_with = "lenient")]
+    pub thrown_exceptions_rate: f64,
+    #[serde(deserialize_with = "lenient")]
+    pub contentions_rate: f64,
+    pub gc: Gc,
+    pub disk_io: DiskIo,
+    pub tcp: Tcp,
+}
+
+#[derive(Debug, Default, Copy, Clone, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct Gc {
+    #[serde(deserialize_with = "lenient")]
+    pub allocation_speed: f64,
+    #[serde(deserialize_with = "lenient")]
+    pub gen0_items_count: i64,
+    #[serde(deserialize_with = "lenient")]
+    pub gen0_size: i64,
+    #[serde(deserialize_with = "lenient")]
+    pub gen1_items_count: i64,
+    #[serde(deserialize_with = "lenient")]
+    pub gen1_size: i64,
+    #[serde(deserialize_with = "lenient")]
+    pub gen2_items_count: i64,
+    #[serde(deserialize_with = "lenient")]
+    pub gen2_size: i64,
+    #[serde(deserialize_with = "lenient")]
+    pub large_heap_size: i64,
+    #[serde(deserialize_with = "lenient")]
+    pub time_in_gc: f64,
+    #[serde(deserialize_with = "lenient")]
+    pub total_bytes_in_heaps: i64,
+}
+
+#[derive(Debug, Default, Copy, Clone, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct DiskIo {
+    #[serde(deserialize_with = "lenient")]
+    pub read_bytes: u64,
+    #[serde(deserialize_with = "lenient")]
+    pub written_bytes: u64,
+    #[serde(deserialize_with = "lenient")]
+    pub read_ops: u64,
+    #[serde(deserialize_with = "lenient")]
+    pub write_ops: u64,
+}
+
+#[derive(Debug, Default, Clone, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct Tcp {
+    #[serde(deserialize_with = "lenient")]
+    pub connections: i64,
+    #[serde(deserialize_with = "lenient")]
+    pub receiving_speed: f64,
+    #[serde(deserialize_with = "lenient")]
+    pub sending_speed: f64,
+    #[serde(deserialize_with = "lenient")]
+    pub in_send: i64,
+    #[serde(deserialize_with = "lenient")]
+    pub measure_time: String,
+    #[serde(deserialize_with = "lenient")]
+    pub pending_received: i64,
+    #[serde(deserialize_with = "lenient")]
+    pub pending_send: i64,
+    #[serde(deserialize_with = "lenient")]
+    pub received_bytes_since_last_run: i64,
+    #[serde(deserialize_with = "lenient")]
+    pub received_bytes_total: u64,
+    #[serde(deserialize_with = "lenient")]
+    pub sent_bytes_since_last_run: i64,
+    #[serde(deserialize_with = "lenient")]
+    pub sent_bytes_total: u64,
+}
+
+#[derive(Debug, Default, Clone, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct Sys {
+    /// Some platforms report a negative value, which is read as `0`.
+    #[serde(deserialize_with = "lenient")]
+    pub free_mem: u64,
+    pub loadavg: LoadAvg,
+    /// Drives, by path.
+    #[serde(rename = "drive")]
+    pub drives: BTreeMap<String, DriveStats>,
+}
+
+#[derive(Debug, Default, Copy, Clone, Deserialize)]
+#[serde(default)]
+pub struct LoadAvg {
+    #[serde(rename = "1m", deserialize_with = "lenient")]
+    pub one_m: f64,
+    #[serde(rename = "5m", deserialize_with = "lenient")]
+    pub five_m: f64,
+    #[serde(rename = "15m", deserialize_with = "lenient")]
+    pub fifteen_m: f64,
+}
+
+#[derive(Debug, Default, Clone, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct DriveStats {
+    #[serde(deserialize_with = "lenient")]
+    pub available_bytes: u64,
+    #[serde(deserialize_with = "lenient")]
+    pub total_bytes: u64,
+    #[serde(deserialize_with = "lenient")]
+    pub usage: String,
+    #[serde(deserialize_with = "lenient")]
+    pub used_bytes: u64,
+}
+
+#[derive(Debug, Default, Clone, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct Es {
+    /// Queues, by name.
+    #[serde(rename = "queue")]
+    pub queues: BTreeMap<String, Queue>,
+    #[serde(deserialize_with = "lenient")]
+    pub checksum: i64,
+    #[serde(deserialize_with = "lenient")]
+    pub checksum_non_flushed: i64,
+    pub writer: Writer,
+    pub read_index: ReadIndex,
+}
+
+#[derive(Debug, Default, Copy, Clone, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct Writer {
+    #[serde(deserialize_with = "lenient")]
+    pub last_flush_size: i64,
+    #[serde(deserialize_with = "lenient")]
+    pub last_flush_delay_ms: f64,
+    #[serde(deserialize_with = "lenient")]
+    pub mean_flush_size: i64,
+    #[serde(deserialize_with = "lenient")]
+    pub mean_flush_delay_ms: f64,
+    #[serde(deserialize_with = "lenient")]
+    pub max_flush_size: i64,
+    #[serde(deserialize_with = "lenient")]
+    pub max_flush_delay_ms: f64,
+    #[serde(deserialize_with = "lenient")]
+    pub queued_flush_messages: i64,
+}
+
+#[derive(Debug, Default, Copy, Clone, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct ReadIndex {
+    #[serde(deserialize_with = "lenient")]
+    pub cached_record: i64,
+    #[serde(deserialize_with = "lenient")]
+    pub not_cached_record: i64,
+    #[serde(deserialize_with = "lenient")]
+    pub cached_stream_info: i64,
+    #[serde(deserialize_with = "lenient")]
+    pub not_cached_stream_info: i64,
+    #[serde(deserialize_with = "lenient")]
+    pub cached_trans_info: i64,
+    #[serde(deserialize_with = "lenient")]
+    pub not_cached_trans_info: i64,
+}
+
+#[derive(Debug, Default, Clone, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct Queue {
+    #[serde(rename = "queueName", deserialize_with = "lenient")]
+    pub name: String,
+    #[serde(deserialize_with = "lenient")]
+    pub group_name: String,
+    #[serde(deserialize_with = "lenient")]
+    pub avg_items_per_second: f64,
+    #[serde(deserialize_with = "lenient_option")]
+    pub current_idle_time: Option<String>,
+    #[serde(deserialize_with = "lenient_option")]
+    pub current_item_processing_time: Option<String>,
+    #[serde(deserialize_with = "lenient")]
+    pub idle_time_percent: f64,
+    #[serde(deserialize_with = "lenient")]
+    pub length_current_try_peak: i64,
+    #[serde(deserialize_with = "lenient")]
+    pub length_lifetime_peak: i64,
+    #[serde(deserialize_with = "lenient")]
+    pub length: i64,
+    #[serde(deserialize_with = "lenient")]
+    pub avg_processing_time: f64,
+    #[serde(deserialize_with = "lenient")]
+    pub total_items_processed: u64,
+    #[serde(deserialize_with = "lenient")]
+    pub in_progress_message: String,
+    #[serde(deserialize_with = "lenient")]
+    pub last_processed_message: String,
+}
+
+impl Statistics {
+    pub fn from_raw(raw: &RawStatistics) -> Self {
+        let mut tree = Map::new();
+
+        for (key, value) in raw.0.iter() {
+            insert(&mut tree, &key_path(key), value);
+        }
+
+        // Can't fail: every field has a default and leaves are parsed leniently.
+        let mut stats = Statistics::deserialize(Value::Object(tree)).unwrap_or_default();
+        stats.raw = raw.0.clone();
+
+        stats
+    }
+}
+
+impl From<&RawStatistics> for Statistics {
+    fn from(raw: &RawStatistics) -> Self {
+        Statistics::from_raw(raw)
+    }
+}
+
+impl RawStatistics {
+    pub fn to_statistics(&self) -> Statistics {
+        Statistics::from_raw(self)
+    }
+}
+
+fn key_path(key: &str) -> Vec<&str> {
+    for prefix in NAMED_PREFIXES {
+        if let Some(rest) = key.strip_prefix(prefix) {
+            let mut path = prefix.trim_end_matches('-').split('-').collect::<Vec<_>>();
+
+            // A named entry must hold properties, anything else would clash with the map type.
+            let Some((name, prop)) = rest.rsplit_once('-') else {
+                return Vec::new();
+            };
+
+            path.extend([name, prop]);
+            return path;
+        }
+    }
+
+    key.split('-').collect()
+}
+
+// On conflicting keys, the first one to claim a path wins.
+fn insert(tree: &mut Map<String, Value>, path: &[&str], value: &str) {
+    let Some((leaf, parents)) = path.split_last() else {
+        return;
+    };
+
+    let mut node = tree;
+    for parent in parents {
+        let entry = node
+            .entry(parent.to_string())
+            .or_insert_with(|| Value::Object(Map::new()));
+
+        match entry {
+            Value::Object(child) => node = child,
+            _ => return,
+        }
+    }
+
+    node.entry(leaf.to_string())
+        .or_insert_with(|| Value::String(value.to_string()));
+}
+
+fn lenient<'de, D, T>(deserializer: D) -> Result<T, D::Error>
+where
+    D: Deserializer<'de>,
+    T: FromStr + Default,
+{
+    let value = Value::deserialize(deserializer)?;
+
+    Ok(value
+        .as_str()
+        .and_then(|s| s.trim().parse().ok())
+        .unwrap_or_default())
+}
+
+fn lenient_option<'de, D, T>(deserializer: D) -> Result<Option<T>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: FromStr,
+{
+    let value = Value::deserialize(deserializer)?;
+
+    Ok(value
+        .as_str()
+        .map(str::trim)
+        .filter(|s| !s.is_empty() && *s != "null")
+        .and_then(|s| s.parse().ok()))
+}
+
+/// Changes between two consecutive [`Statistics`]. Rates are per second.
+#[derive(Debug, Default, Clone)]
+pub struct StatsDelta {
+    /// Time between the two samples, as observed by the client.
+    pub elapsed: Duration,
+    pub disk_bytes_read_per_sec: f64,
+    pub disk_bytes_written_per_sec: f64,
+    pub disk_read_ops_per_sec: f64,
+    pub disk_write_ops_per_sec: f64,
+    pub tcp_bytes_received_per_sec: f64,
+    pub tcp_bytes_sent_per_sec: f64,
+    /// Queues present in both samples, by name.
+    pub queues: BTreeMap<String, QueueDelta>,
+}
+
+#[derive(Debug, Default, Clone, Copy)]
+pub struct QueueDelta {
+    /// Length difference, positive when the queue grows.
+    pub length_change: i64,
+    pub items_processed_per_sec: f64,
+}
+
+impl StatsDelta {
+    /// Computes the changes from `previous` to `current`, `elapsed` apart. Counters that went
+    /// down, like after a server restart, are considered reset.
+    pub fn between(previous: &Statistics, current: &Statistics, elapsed: Duration) -> Self {
+        let secs = elapsed.as_secs_f64();
+        let prev_io = &previous.proc.disk_io;
+        let io = &current.proc.disk_io;
+
+        let queues = current
+            .es
+            .queues
+            .iter()
+            .filter_map(|(name, queue)| {
+                let prev = previous.es.queues.get(name)?;
+
+                Some((
+                    name.clone(),
+                    QueueDelta {
+                        length_change: queue.length - prev.length,
+                        items_processed_per_sec: rate(
+                            prev.total_items_processed,
+                            queue.total_items_processed,
+                            secs,
+                        ),
+                    },
+                ))
+            })
+            .collect();
+
+        Self {
+            elapsed,
+            disk_bytes_read_per_sec: rate(prev_io.read_bytes, io.read_bytes, secs),
+            disk_bytes_written_per_sec: rate(prev_io.written_bytes, io.written_bytes, secs),
+            disk_read_ops_per_sec: rate(prev_io.read_ops, io.read_ops, secs),
+            disk_write_ops_per_sec: rate(prev_io.write_ops, io.write_ops, secs),
+            tcp_bytes_received_per_sec: rate(
+                previous.proc.tcp.received_bytes_total,
+                current.proc.tcp.received_bytes_total,
+                secs,
+            ),
+            tcp_bytes_sent_per_sec: rate(
+                previous.proc.tcp.sent_bytes_total,
+                current.proc.tcp.sent_bytes_total,
+                secs,
+            ),
+            queues,
+        }
+    }
+}
+
+fn rate(previous: u64, current: u64, secs: f64) -> f64 {
+    if secs <= 0.0 {
+        return 0.0;
+    }
+
+    let delta = if current >= previous {
+        current - previous
+    } else {
+        current
+    };
+
+    delta as f64 / secs
+}
+
+/// A [`Statistics`] sample along with its changes since the previous one.
+#[derive(Debug, Clone)]
+pub struct StatsSample {
+    pub statistics: Statistics,
+    /// `None` for the first sample.
+    pub delta: Option<StatsDelta>,
+}
+
+/// Turns the [`Stats`] stream into [`StatsSample`]s. See [`Client::watch_stats`].
+pub struct StatsWatcher {
+    inner: Stats,
+    previous: Option<(Instant, Statistics)>,
+}
+
+impl StatsWatcher {
+    pub fn new(inner: Stats) -> Self {
+        Self {
+            inner,
+            previous: None,
+        }
+    }
+
+    pub async fn next(&mut self) -> crate::Result<StatsSample> {
+        let raw = self.inner.next().await?;
+        let now = Instant::now();
+        let statistics = raw.to_statistics();
+
+        let delta = self
+            .previous
+            .as_ref()
+            .map(|(at, prev)| StatsDelta::between(prev, &statistics, now.duration_since(*at)));
+
+        self.previous = Some((now, statistics.clone()));
+
+        Ok(StatsSample { statistics, delta })
+    }
+}
+
+impl From<Stats> for StatsWatcher {
+    fn from(inner: Stats) -> Self {
+        StatsWatcher::new(inner)
+    }
+}
+
+impl Client {
+    /// Like [`Client::stats`], but yields typed statistics along with their per-interval changes.
+    pub async fn watch_stats(&self, options: &StatsOptions) -> crate::Result<StatsWatcher> {
+        Ok(StatsWatcher::new(self.stats(options).await?))
+    }
+}
+
+#[cfg(test)]
+mod stats_tests {
+    use super::*;
+
+    fn raw(entries: &[(&str, &str)]) -> RawStatistics {
+        RawStatistics(
+            entries
+                .iter()
+                .map(|(k, v)| (k.to_string(), v.to_string()))
+                .collect(),
+        )
+    }
+
+    #[test]
+    fn test_from_raw() {
+        let stats = raw(&[
+            ("proc-id", "42"),
+            ("proc-startTime", "2024-01-02T03:04:05Z"),
+            ("proc-gc-gen0Size", "1024"),
+            ("proc-diskIo-writtenBytes", "2048"),
+            ("sys-freeMem", "-1"),
+            ("sys-loadavg-1m", "0.5"),
+            ("sys-drive-/var/lib/kurrentdb-availableBytes", "100"),
+            ("es-queue-Worker #1-length", "3"),
+            ("es-queue-Worker #1-currentIdleTime", "null"),
+            ("es-writer-meanFlushDelayMs", "1.5"),
+            ("es-somethingNew-value", "1"),
+            ("proc-mem", "not a number"),
+        ])
+        .to_statistics();
+
+        assert_eq!(stats.proc.id, 42);
+        assert!(stats.proc.start_time.is_some());
+        assert_eq!(stats.proc.gc.gen0_size, 1024);
+        assert_eq!(stats.proc.disk_io.written_bytes, 2048);
+        assert_eq!(stats.proc.mem, 0);
+        assert_eq!(stats.sys.free_mem, 0);
+        assert_eq!(stats.sys.loadavg.one_m, 0.5);
+        assert_eq!(stats.sys.drives["/var/lib/kurrentdb"].available_bytes, 100);
+        assert_eq!(stats.es.queues["Worker #1"].length, 3);
+        assert_eq!(stats.es.queues["Worker #1"].current_idle_time, None);
+        assert_eq!(stats.es.writer.mean_flush_delay_ms, 1.5);
+        assert_eq!(stats.raw.len(), 12);
+    }
+
+    #[test]
+    fn test_delta() {
+        let previous = raw(&[
+            ("proc-diskIo-writtenBytes", "1000"),
+            ("es-queue-Main-length", "10"),
+            ("es-queue-Main-totalItemsProcessed", "100"),
+        ])
+        .to_statistics();
+
+        let current = raw(&[
+            ("proc-diskIo-writtenBytes", "3000"),
+            ("es-queue-Main-length", "4"),
+            ("es-queue-Main-totalItemsProcessed", "150"),
+            ("es-queue-Other-length", "1"),
+        ])
+        .to_statistics();
+
+        let delta = StatsDelta::between(&previous, &current, Duration::from_secs(2));
+
+        assert_eq!(delta.disk_bytes_written_per_sec, 1000.0);
+        assert_eq!(delta.queues["Main"].length_change, -6);
+        assert_eq!(delta.queues["Main"].items_processed_per_sec, 25.0);
+        assert!(!delta.queues.contains_key("Other"));
+
+        let reset = StatsDelta::between(&current, &previous, Duration::from_secs(1));
+        assert_eq!(reset.disk_bytes_written_per_sec, 1000.0);
+    }
+}
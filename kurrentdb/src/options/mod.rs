@@ -8,11 +8,15 @@ pub mod delete_stream;
 pub mod persistent_subscription;
 pub mod projections;
 pub mod read_all;
+pub mod read_event;
 pub mod read_stream;
 pub mod retry;
 pub mod subscribe_to_all;
 pub mod subscribe_to_stream;
+pub mod subscribe_to_streams;
+pub mod system_settings;
 pub mod tombstone_stream;
+pub mod update_stream_metadata;
 
 pub(crate) trait Options {
     fn common_operation_options(&self) -> &CommonOperationOptions;
@@ -24,6 +28,8 @@ pub(crate) struct CommonOperationOptions {
     pub(crate) credentials: Option<Credentials>,
     pub(crate) requires_leader: bool,
     pub(crate) deadline: Option<Duration>,
+    pub(crate) retry_policy: Option<retry::RetryPolicy>,
+    pub(crate) tls_identity: Option<String>,
 }
 
 #[derive(Clone, Copy, PartialEq, Eq)]
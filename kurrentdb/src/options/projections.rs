@@ -1,4 +1,8 @@
-use eventstore_macros::options;
+use std::time::Duration;
+
+use crate::options::retry::RetryOptions;
+use crate::{Position, StreamPosition};
+use eventstore_macros::{options, streaming};
 
 options! {
     #[derive(Clone, Default)]
@@ -125,3 +129,125 @@ options! {
     #[derive(Clone, Default)]
     pub struct GenericProjectionOptions {}
 }
+
+options! {
+    #[derive(Clone)]
+    /// Options of [`crate::ProjectionClient::run_query`].
+    pub struct RunQueryOptions {
+        pub(crate) partition: String,
+        pub(crate) poll_interval: Duration,
+        pub(crate) timeout: Option<Duration>,
+    }
+}
+
+impl Default for RunQueryOptions {
+    fn default() -> Self {
+        Self {
+            partition: String::new(),
+            poll_interval: Duration::from_millis(500),
+            timeout: None,
+            common_operation_options: Default::default(),
+        }
+    }
+}
+
+impl RunQueryOptions {
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Partition whose result is returned.
+    pub fn partition(self, value: impl AsRef<str>) -> Self {
+        Self {
+            partition: value.as_ref().to_string(),
+            ..self
+        }
+    }
+
+    /// How long to wait between two status checks. Default: `500ms`.
+    pub fn poll_interval(self, poll_interval: Duration) -> Self {
+        Self {
+            poll_interval,
+            ..self
+        }
+    }
+
+    /// How long to wait for the query to complete. Waits indefinitely by default.
+    pub fn timeout(self, timeout: Duration) -> Self {
+        Self {
+            timeout: Some(timeout),
+            ..self
+        }
+    }
+}
+
+options! {
+    #[derive(Clone)]
+    /// Options of [`crate::ProjectionClient::get_partition_states`].
+    pub struct GetPartitionStatesOptions {
+        pub(crate) concurrency: usize,
+    }
+}
+
+impl Default for GetPartitionStatesOptions {
+    fn default() -> Self {
+        Self {
+            concurrency: 8,
+            common_operation_options: Default::default(),
+        }
+    }
+}
+
+impl GetPartitionStatesOptions {
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// How many partition states are fetched at the same time. Default: `8`.
+    pub fn concurrency(self, concurrency: usize) -> Self {
+        Self {
+            concurrency: concurrency.max(1),
+            ..self
+        }
+    }
+}
+
+options! {
+    #[derive(Clone)]
+    #[streaming]
+    /// Options of [`crate::ProjectionClient::subscribe_to_partition_results`].
+    pub struct SubscribeToPartitionResultsOptions {
+        pub(crate) position: StreamPosition<Position>,
+        pub(crate) retry: Option<RetryOptions>,
+    }
+}
+
+impl Default for SubscribeToPartitionResultsOptions {
+    fn default() -> Self {
+        Self {
+            position: StreamPosition::Start,
+            retry: None,
+            common_operation_options: Default::default(),
+        }
+    }
+}
+
+impl SubscribeToPartitionResultsOptions {
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Position in the `$all` stream to start from. By default, it starts at
+    /// `StreamPosition::Start`, which replays every result written so far.
+    pub fn position(self, position: StreamPosition<Position>) -> Self {
+        Self { position, ..self }
+    }
+
+    /// When a disconnection happens, automatically resubscribe from the last result received.
+    pub fn retry_options(self, options: RetryOptions) -> Self {
+        Self {
+            retry: Some(options),
+            ..self
+        }
+    }
+}
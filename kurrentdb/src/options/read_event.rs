@@ -0,0 +1,27 @@
+use eventstore_macros::options;
+
+options! {
+    #[derive(Clone, Default)]
+    /// Options of the read event and read last event commands.
+    pub struct ReadEventOptions {
+        pub(crate) resolve_link_tos: bool,
+    }
+}
+
+impl ReadEventOptions {
+    /// When using projections, you can have links placed into another stream.
+    /// If you set `true`, the server will resolve those links and will return
+    /// the event that the link points to. Default: `false`.
+    pub fn resolve_link_tos(self) -> Self {
+        Self {
+            resolve_link_tos: true,
+            ..self
+        }
+    }
+}
+
+options! {
+    #[derive(Clone, Default)]
+    /// Options of the get stream status command.
+    pub struct GetStreamStatusOptions {}
+}
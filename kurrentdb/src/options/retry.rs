@@ -30,3 +30,106 @@ impl RetryOptions {
         Self { delay, ..self }
     }
 }
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+/// Exponential backoff with jitter retry policy, applied to unary commands. Streaming operations,
+/// like reading a stream or subscribing, are never retried.
+///
+/// Commands that aren't idempotent are only retried when the server is known to not have
+/// processed them, see [`crate::Error::is_retryable`]. Idempotent commands, like unary lookups or
+/// appends where every event has an explicit id, are also retried on
+/// [`crate::Error::is_transient`] errors.
+///
+/// The default policy, also used by [`crate::ClientSettings`] when the connection string doesn't
+/// override it, never retries. Retries are opted into with [`RetryPolicy::max_retries`] or the
+/// `maxRetries` connection string setting.
+pub struct RetryPolicy {
+    pub(crate) max_retries: usize,
+    pub(crate) initial_backoff: std::time::Duration,
+    pub(crate) max_backoff: std::time::Duration,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_retries: 0,
+            initial_backoff: std::time::Duration::from_millis(100),
+            max_backoff: std::time::Duration::from_secs(5),
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Never retries a failing command.
+    pub fn no_retry() -> Self {
+        Self::default().max_retries(0)
+    }
+
+    /// Sets how many times we retry a failing command before giving up.
+    pub fn max_retries(self, max_retries: usize) -> Self {
+        Self {
+            max_retries,
+            ..self
+        }
+    }
+
+    /// Sets how long we wait before the first retry. That delay doubles on each following
+    /// attempt.
+    pub fn initial_backoff(self, initial_backoff: std::time::Duration) -> Self {
+        Self {
+            initial_backoff,
+            ..self
+        }
+    }
+
+    /// Sets the upper bound of the delay between two attempts.
+    pub fn max_backoff(self, max_backoff: std::time::Duration) -> Self {
+        Self {
+            max_backoff,
+            ..self
+        }
+    }
+
+    /// Delay to wait before the given retry attempt, starting at 0. Half of it is random so
+    /// concurrent clients don't retry in lockstep.
+    pub(crate) fn backoff(&self, attempt: usize) -> std::time::Duration {
+        use rand::Rng;
+
+        let factor = 1u32.checked_shl(attempt as u32).unwrap_or(u32::MAX);
+        let delay = self
+            .initial_backoff
+            .saturating_mul(factor)
+            .min(self.max_backoff);
+        let half = delay / 2;
+
+        half + half.mul_f64(rand::thread_rng().r#gen::<f64>())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::RetryPolicy;
+    use std::time::Duration;
+
+    #[test]
+    fn backoff_grows_exponentially_up_to_max() {
+        let policy = RetryPolicy::default()
+            .initial_backoff(Duration::from_millis(100))
+            .max_backoff(Duration::from_secs(1));
+
+        for (attempt, expected) in [
+            (0, 100),
+            (1, 200),
+            (2, 400),
+            (3, 800),
+            (4, 1_000),
+            (64, 1_000),
+        ] {
+            let delay = policy.backoff(attempt);
+            let expected = Duration::from_millis(expected);
+
+            assert!(delay >= expected / 2, "attempt {attempt}: {delay:?}");
+            assert!(delay <= expected, "attempt {attempt}: {delay:?}");
+        }
+    }
+}
@@ -0,0 +1,44 @@
+use crate::StreamsCheckpoint;
+use crate::options::retry::RetryOptions;
+use eventstore_macros::{options, streaming};
+
+options! {
+    #[derive(Clone, Default)]
+    #[streaming]
+    /// Options of the subscribe to streams command.
+    pub struct SubscribeToStreamsOptions {
+        pub(crate) checkpoint: Option<StreamsCheckpoint>,
+        pub(crate) resolve_link_tos: bool,
+        pub(crate) retry: Option<RetryOptions>,
+    }
+}
+
+impl SubscribeToStreamsOptions {
+    /// Resumes right after the events covered by a checkpoint of a previous subscription. By
+    /// default, the subscription starts at the beginning of the transaction log.
+    pub fn checkpoint(self, checkpoint: StreamsCheckpoint) -> Self {
+        Self {
+            checkpoint: Some(checkpoint),
+            ..self
+        }
+    }
+
+    /// When using projections, you can have links placed into another stream.
+    /// If you set `true`, the server will resolve those links and will return
+    /// the event that the link points to. Default: `false`.
+    pub fn resolve_link_tos(self) -> Self {
+        Self {
+            resolve_link_tos: true,
+            ..self
+        }
+    }
+
+    /// When a disconnection happens, automatically resubscribe to stream changes. When enabled,
+    /// The client will keep track of the current subscription offset.
+    pub fn retry_options(self, options: RetryOptions) -> Self {
+        Self {
+            retry: Some(options),
+            ..self
+        }
+    }
+}
@@ -0,0 +1,64 @@
+use crate::StreamState;
+use eventstore_macros::options;
+
+options! {
+    #[derive(Clone, Default)]
+    /// Options of the get system settings command.
+    pub struct GetSystemSettingsOptions {}
+}
+
+options! {
+    #[derive(Clone)]
+    /// Options of the set system settings command.
+    pub struct SetSystemSettingsOptions {
+        pub(crate) stream_state: StreamState,
+    }
+}
+
+impl Default for SetSystemSettingsOptions {
+    fn default() -> Self {
+        Self {
+            stream_state: StreamState::Any,
+            common_operation_options: Default::default(),
+        }
+    }
+}
+
+impl SetSystemSettingsOptions {
+    /// Asks the server to check that the `$settings` stream is at the given stream state, usually
+    /// the version of previously read settings. Default: `StreamState::Any`.
+    pub fn stream_state(self, stream_state: StreamState) -> Self {
+        Self {
+            stream_state,
+            ..self
+        }
+    }
+}
+
+options! {
+    #[derive(Clone)]
+    /// Options of the update system settings command.
+    pub struct UpdateSystemSettingsOptions {
+        pub(crate) max_attempts: usize,
+    }
+}
+
+impl Default for UpdateSystemSettingsOptions {
+    fn default() -> Self {
+        Self {
+            max_attempts: 10,
+            common_operation_options: Default::default(),
+        }
+    }
+}
+
+impl UpdateSystemSettingsOptions {
+    /// How many times the settings are read, updated and written before giving up when they keep
+    /// being concurrently modified. Default: `10`.
+    pub fn max_attempts(self, max_attempts: usize) -> Self {
+        Self {
+            max_attempts: max_attempts.max(1),
+            ..self
+        }
+    }
+}
@@ -0,0 +1,29 @@
+use eventstore_macros::options;
+
+options! {
+    #[derive(Clone)]
+    /// Options of the update stream metadata command.
+    pub struct UpdateStreamMetadataOptions {
+        pub(crate) max_attempts: usize,
+    }
+}
+
+impl Default for UpdateStreamMetadataOptions {
+    fn default() -> Self {
+        Self {
+            max_attempts: 10,
+            common_operation_options: Default::default(),
+        }
+    }
+}
+
+impl UpdateStreamMetadataOptions {
+    /// How many times the value is read, updated and written before giving up when it keeps
+    /// being concurrently modified. Default: `10`.
+    pub fn max_attempts(self, max_attempts: usize) -> Self {
+        Self {
+            max_attempts: max_attempts.max(1),
+            ..self
+        }
+    }
+}
@@ -1,18 +1,36 @@
+use crate::commands::Subscription;
 use crate::event_store::client::projections;
 use crate::grpc::{ClientSettings, GrpcClient};
 use crate::options::projections::{
     CreateProjectionOptions, DeleteProjectionOptions, GenericProjectionOptions,
-    GetResultProjectionOptions, GetStateProjectionOptions, UpdateProjectionOptions,
+    GetPartitionStatesOptions, GetResultProjectionOptions, GetStateProjectionOptions,
+    RunQueryOptions, SubscribeToPartitionResultsOptions, UpdateProjectionOptions,
 };
-use futures::{TryStreamExt, stream::BoxStream};
+use crate::options::read_stream::ReadStreamOptions;
+use crate::options::subscribe_to_all::SubscribeToAllOptions;
+use crate::types::{Position, SubscriptionFilter, TlsIdentity, escape_regex};
+use futures::{StreamExt, TryStreamExt, stream::BoxStream};
+use serde::Deserialize;
 use serde::de::DeserializeOwned;
+use std::collections::HashSet;
+use std::fmt::{Display, Formatter};
+use std::marker::PhantomData;
+use std::str::FromStr;
+use std::time::{Duration, Instant};
+use tracing::warn;
+
+const WAIT_INITIAL_BACKOFF: Duration = Duration::from_millis(100);
+const WAIT_MAX_BACKOFF: Duration = Duration::from_secs(2);
+const PROJECTION_UPDATED_EVENT_TYPE: &str = "$ProjectionUpdated";
+const RESULT_EVENT_TYPE: &str = "Result";
 
-#[allow(dead_code)]
 #[derive(Clone, Debug)]
 pub(crate) enum StatsFor {
     Name(String),
     AllProjections,
     AllContinuous,
+    AllTransient,
+    AllOneTime,
 }
 
 #[derive(Clone, Debug)]
@@ -24,20 +42,356 @@ pub struct ProjectionStatus {
     pub writes_in_progress: i32,
     pub reads_in_progress: i32,
     pub partitions_cached: i32,
-    pub status: String,
+    pub status: ProjectionRunStatus,
     pub state_reason: String,
     pub name: String,
-    pub mode: String,
-    pub position: String,
+    pub mode: ProjectionMode,
+    pub position: ProjectionPosition,
     pub progress: f32,
-    pub last_checkpoint: String,
+    pub last_checkpoint: ProjectionPosition,
     pub events_processed_after_restart: i64,
-    pub checkpoint_status: String,
+    pub checkpoint_status: CheckpointStatus,
     pub buffered_events: i64,
     pub write_pending_events_before_checkpoint: i32,
     pub write_pending_events_after_checkpoint: i32,
 }
 
+/// State of a projection, the first part of its status.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ProjectionState {
+    Creating,
+    Loading,
+    Loaded,
+    Preparing,
+    Prepared,
+    Starting,
+    LoadingStopped,
+    Running,
+    Stopping,
+    Aborting,
+    Stopped,
+    Completed,
+    Aborted,
+    Faulted,
+    Deleting,
+    /// A state this client doesn't know about.
+    Unknown(String),
+}
+
+impl FromStr for ProjectionState {
+    type Err = std::convert::Infallible;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let state = match s {
+            "Creating" => ProjectionState::Creating,
+            "Loading" => ProjectionState::Loading,
+            "Loaded" => ProjectionState::Loaded,
+            "Preparing" => ProjectionState::Preparing,
+            "Prepared" => ProjectionState::Prepared,
+            "Starting" => ProjectionState::Starting,
+            "LoadingStopped" => ProjectionState::LoadingStopped,
+            "Running" => ProjectionState::Running,
+            "Stopping" => ProjectionState::Stopping,
+            "Aborting" => ProjectionState::Aborting,
+            "Stopped" => ProjectionState::Stopped,
+            "Completed" => ProjectionState::Completed,
+            "Aborted" => ProjectionState::Aborted,
+            "Faulted" => ProjectionState::Faulted,
+            "Deleting" => ProjectionState::Deleting,
+            other => ProjectionState::Unknown(other.to_string()),
+        };
+
+        Ok(state)
+    }
+}
+
+/// Status of a projection as reported by the server, e.g. `Running`, `Faulted (Enabled)` or
+/// `Completed/Stopped/Writing results`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ProjectionRunStatus {
+    raw: String,
+    /// Main state of the projection.
+    pub state: ProjectionState,
+    /// Additional steps, e.g. `Stopped` and `Writing results` in
+    /// `Completed/Stopped/Writing results`.
+    pub details: Vec<String>,
+    /// The projection is enabled, even though it's not running.
+    pub enabled: bool,
+}
+
+impl ProjectionRunStatus {
+    pub fn is_running(&self) -> bool {
+        self.state == ProjectionState::Running
+    }
+
+    pub fn is_faulted(&self) -> bool {
+        self.state == ProjectionState::Faulted
+    }
+
+    pub fn is_stopped(&self) -> bool {
+        self.state == ProjectionState::Stopped || self.details.iter().any(|d| d == "Stopped")
+    }
+
+    /// The projection reached the end of its events and wrote its results.
+    pub fn is_completed(&self) -> bool {
+        self.state == ProjectionState::Completed
+            && !self.details.iter().any(|d| d.starts_with("Writing"))
+    }
+
+    /// The status as sent by the server.
+    pub fn as_str(&self) -> &str {
+        self.raw.as_str()
+    }
+}
+
+impl FromStr for ProjectionRunStatus {
+    type Err = std::convert::Infallible;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (status, enabled) = match s.strip_suffix(" (Enabled)") {
+            Some(status) => (status, true),
+            None => (s, false),
+        };
+
+        let mut parts = status.split('/');
+        let state = parts.next().unwrap_or_default().parse()?;
+
+        Ok(ProjectionRunStatus {
+            raw: s.to_string(),
+            state,
+            details: parts.map(str::to_string).collect(),
+            enabled,
+        })
+    }
+}
+
+impl Display for ProjectionRunStatus {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.raw)
+    }
+}
+
+/// How a projection runs.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ProjectionMode {
+    Continuous,
+    OneTime,
+    Transient,
+    /// A mode this client doesn't know about.
+    Unknown(String),
+}
+
+impl FromStr for ProjectionMode {
+    type Err = std::convert::Infallible;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mode = match s {
+            "Continuous" => ProjectionMode::Continuous,
+            "OneTime" => ProjectionMode::OneTime,
+            "Transient" => ProjectionMode::Transient,
+            other => ProjectionMode::Unknown(other.to_string()),
+        };
+
+        Ok(mode)
+    }
+}
+
+/// Position of a projection in the events it processes.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ProjectionPosition {
+    /// The projection didn't process any event yet.
+    None,
+    /// Position in the `$all` stream, e.g. `C:1234/P:1234`.
+    All(Position),
+    /// Revision reached in each stream the projection reads, e.g. `$ce-account: 42`.
+    Streams(Vec<(String, i64)>),
+    /// Phase reached by a projection going through several ones, like a one-time projection
+    /// writing its results, e.g. `Phase: 1` or `Phase: 1 (completed)`.
+    Phase { phase: i64, completed: bool },
+    /// A position this client doesn't know how to parse.
+    Unknown(String),
+}
+
+impl FromStr for ProjectionPosition {
+    type Err = std::convert::Infallible;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+
+        if s.is_empty() {
+            return Ok(ProjectionPosition::None);
+        }
+
+        let all = s.split_once('/').and_then(|(commit, prepare)| {
+            Some(Position {
+                commit: commit.strip_prefix("C:")?.parse().ok()?,
+                prepare: prepare.strip_prefix("P:")?.parse().ok()?,
+            })
+        });
+
+        if let Some(position) = all {
+            return Ok(ProjectionPosition::All(position));
+        }
+
+        if let Some(phase) = s.strip_prefix("Phase:") {
+            let phase = phase.trim();
+            let (phase, completed) = match phase.strip_suffix("(completed)") {
+                Some(phase) => (phase.trim(), true),
+                None => (phase, false),
+            };
+
+            return Ok(match phase.parse() {
+                Ok(phase) => ProjectionPosition::Phase { phase, completed },
+                Err(_) => ProjectionPosition::Unknown(s.to_string()),
+            });
+        }
+
+        let streams = s
+            .split(';')
+            .map(|part| {
+                let (stream, revision) = part.trim().rsplit_once(':')?;
+                Some((stream.trim().to_string(), revision.trim().parse().ok()?))
+            })
+            .collect::<Option<Vec<_>>>();
+
+        Ok(match streams {
+            Some(streams) => ProjectionPosition::Streams(streams),
+            None => ProjectionPosition::Unknown(s.to_string()),
+        })
+    }
+}
+
+/// Progress of the projection checkpoint.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum CheckpointStatus {
+    /// No checkpoint is in progress.
+    Idle,
+    /// A checkpoint was requested.
+    Requested,
+    /// A checkpoint is being written.
+    Writing,
+    /// A status this client doesn't know about.
+    Unknown(String),
+}
+
+impl FromStr for CheckpointStatus {
+    type Err = std::convert::Infallible;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let status = match s.trim() {
+            "" => CheckpointStatus::Idle,
+            s if s.contains("Requested") => CheckpointStatus::Requested,
+            s if s.starts_with("Writing") => CheckpointStatus::Writing,
+            other => CheckpointStatus::Unknown(other.to_string()),
+        };
+
+        Ok(status)
+    }
+}
+
+/// Definition of a projection, as last stored by the server.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ProjectionDefinition {
+    pub name: String,
+    pub query: String,
+    pub mode: ProjectionMode,
+    pub enabled: bool,
+    pub emit_enabled: bool,
+    pub track_emitted_streams: bool,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct PersistedState {
+    #[serde(default)]
+    query: String,
+    #[serde(default)]
+    mode: serde_json::Value,
+    #[serde(default)]
+    enabled: bool,
+    #[serde(default)]
+    emit_enabled: bool,
+    #[serde(default)]
+    track_emitted_streams: bool,
+}
+
+fn parse_infallible<A: FromStr<Err = std::convert::Infallible>>(s: &str) -> A {
+    match s.parse() {
+        Ok(value) => value,
+        Err(never) => match never {},
+    }
+}
+
+/// Results of the partitions of a projection, as they are written. See
+/// [`ProjectionClient::subscribe_to_partition_results`].
+pub struct PartitionResultSubscription<A> {
+    inner: Subscription,
+    client: ProjectionClient,
+    name: String,
+    options: GenericProjectionOptions,
+    prefix: String,
+    partitions: HashSet<String>,
+    // Names missing from the catalog after a refresh, so they don't trigger more refreshes.
+    foreign: HashSet<String>,
+    _marker: PhantomData<A>,
+}
+
+impl<A> PartitionResultSubscription<A>
+where
+    A: DeserializeOwned,
+{
+    /// Waits for the next result, along with the partition it belongs to.
+    pub async fn next(&mut self) -> crate::Result<(String, serde_json::Result<A>)> {
+        loop {
+            let event = self.inner.next().await?;
+            let event = event.get_original_event();
+
+            if event.event_type != RESULT_EVENT_TYPE {
+                continue;
+            }
+
+            let partition = event
+                .stream_id()
+                .strip_prefix(self.prefix.as_str())
+                .and_then(|rest| rest.strip_suffix("-result"));
+
+            if let Some(partition) = partition
+                && self.is_partition(partition).await?
+            {
+                return Ok((partition.to_string(), serde_json::from_slice(&event.data)));
+            }
+        }
+    }
+
+    /// Tells apart the partitions of this projection from result streams of other projections
+    /// whose name starts with `<name>-`, by looking them up in its partition catalog. The catalog
+    /// is read again at most once per unknown name.
+    async fn is_partition(&mut self, partition: &str) -> crate::Result<bool> {
+        if self.partitions.contains(partition) {
+            return Ok(true);
+        }
+
+        if self.foreign.contains(partition) {
+            return Ok(false);
+        }
+
+        self.partitions = self
+            .client
+            .list_partitions(self.name.as_str(), &self.options)
+            .await?
+            .into_iter()
+            .collect();
+
+        if self.partitions.contains(partition) {
+            return Ok(true);
+        }
+
+        self.foreign.insert(partition.to_string());
+
+        Ok(false)
+    }
+}
+
 #[derive(Clone)]
 pub struct ProjectionClient {
     client: GrpcClient,
@@ -58,6 +412,22 @@ impl ProjectionClient {
         self.client.connection_settings()
     }
 
+    /// Registers a TLS client certificate under the given name. Operations performed with the
+    /// `tls_identity` option set to that name use it to authenticate, on their own connections.
+    /// Registering an identity under an existing name replaces it.
+    pub fn add_tls_identity(
+        &self,
+        name: impl Into<String>,
+        identity: &TlsIdentity,
+    ) -> crate::Result<()> {
+        self.client.add_tls_identity(name.into(), identity)
+    }
+
+    /// Unregisters a TLS client certificate. Returns `false` if no identity had that name.
+    pub fn remove_tls_identity(&self, name: impl AsRef<str>) -> bool {
+        self.client.remove_tls_identity(name.as_ref())
+    }
+
     pub async fn create<Name>(
         &self,
         name: Name,
@@ -92,6 +462,464 @@ impl ProjectionClient {
         Ok(())
     }
 
+    /// Creates a one-time projection. It processes the events once, then stops. The server names
+    /// it, use [`ProjectionClient::list_one_time`] to find it, or [`ProjectionClient::run_query`]
+    /// to get its result in a single call.
+    pub async fn create_one_time(
+        &self,
+        query: String,
+        options: &GenericProjectionOptions,
+    ) -> crate::Result<()> {
+        self.create_projection_internal(
+            options,
+            projections::create_req::Options {
+                query,
+                mode: Some(projections::create_req::options::Mode::OneTime(())),
+            },
+        )
+        .await
+    }
+
+    /// Creates a transient projection. It only lives in memory and doesn't survive a restart of
+    /// the node.
+    pub async fn create_transient<Name>(
+        &self,
+        name: Name,
+        query: String,
+        options: &GenericProjectionOptions,
+    ) -> crate::Result<()>
+    where
+        Name: AsRef<str>,
+    {
+        self.create_projection_internal(
+            options,
+            projections::create_req::Options {
+                query,
+                mode: Some(projections::create_req::options::Mode::Transient(
+                    projections::create_req::options::Transient {
+                        name: name.as_ref().to_string(),
+                    },
+                )),
+            },
+        )
+        .await
+    }
+
+    /// Runs an ad-hoc query over existing events as a one-time projection and returns its result
+    /// once it completes.
+    ///
+    /// One-time projections are named by the server, so the query is prefixed with a comment
+    /// holding a unique tag, which is how the projection is found among the new one-time
+    /// projections. The projection is deleted afterward, including when the query fails, times
+    /// out or the returned future is dropped.
+    pub async fn run_query<A>(
+        &self,
+        query: String,
+        options: &RunQueryOptions,
+    ) -> crate::Result<serde_json::Result<A>>
+    where
+        A: DeserializeOwned + Send,
+    {
+        let generic_options = GenericProjectionOptions {
+            common_operation_options: options.common_operation_options.clone(),
+        };
+        let target = QueryProjection {
+            tag: format!("// query {}\n", uuid::Uuid::new_v4()),
+            known: self.one_time_names(&generic_options).await?,
+            name: None,
+        };
+        let query = format!("{}{}", target.tag, query);
+        let mut cleanup = QueryCleanup::new(self.clone(), target, &generic_options);
+
+        // When this fails, the server may still have created the projection, the cleanup looks
+        // for it either way.
+        self.create_one_time(query, &generic_options).await?;
+
+        let name = self
+            .find_query(&cleanup.target, &generic_options)
+            .await?
+            .ok_or_else(|| {
+                crate::Error::IllegalStateError("query projection not found".to_string())
+            })?;
+
+        cleanup.found(name.clone());
+
+        let result = match self.wait_for_completion(&name, options).await {
+            Ok(()) => {
+                let result_options = GetResultProjectionOptions {
+                    partition: options.partition.clone(),
+                    common_operation_options: options.common_operation_options.clone(),
+                };
+
+                self.get_result(&name, &result_options).await
+            }
+
+            Err(e) => Err(e),
+        };
+
+        cleanup.run().await;
+
+        result
+    }
+
+    async fn one_time_names(
+        &self,
+        options: &GenericProjectionOptions,
+    ) -> crate::Result<HashSet<String>> {
+        self.list_one_time(options)
+            .await?
+            .map_ok(|status| status.name)
+            .try_collect()
+            .await
+    }
+
+    /// Looks for the projection of a query among the one-time projections created since the
+    /// query started.
+    async fn find_query(
+        &self,
+        target: &QueryProjection,
+        options: &GenericProjectionOptions,
+    ) -> crate::Result<Option<String>> {
+        let names = self.one_time_names(options).await?;
+
+        for name in names.difference(&target.known) {
+            if let Some(definition) = self.get_definition(name, options).await?
+                && definition.query.starts_with(&target.tag)
+            {
+                return Ok(Some(definition.name));
+            }
+        }
+
+        Ok(None)
+    }
+
+    async fn wait_for_completion(
+        &self,
+        name: &str,
+        options: &RunQueryOptions,
+    ) -> crate::Result<()> {
+        let generic_options = GenericProjectionOptions {
+            common_operation_options: options.common_operation_options.clone(),
+        };
+
+        self.poll_status(
+            name,
+            options.timeout,
+            (options.poll_interval, options.poll_interval),
+            &generic_options,
+            |status| is_query_done(&status.status, status.progress),
+        )
+        .await?;
+
+        Ok(())
+    }
+
+    /// Polls the status of a projection, with backoff, until `predicate` holds. Fails with
+    /// [`crate::Error::ProjectionFaulted`] if the projection faults before, unless `predicate`
+    /// accepts faulted projections, and with [`crate::Error::DeadlineExceeded`] after `timeout`.
+    pub async fn wait_until<Name, F>(
+        &self,
+        name: Name,
+        timeout: Duration,
+        options: &GenericProjectionOptions,
+        predicate: F,
+    ) -> crate::Result<ProjectionStatus>
+    where
+        Name: AsRef<str>,
+        F: FnMut(&ProjectionStatus) -> bool,
+    {
+        self.poll_status(
+            name.as_ref(),
+            Some(timeout),
+            (WAIT_INITIAL_BACKOFF, WAIT_MAX_BACKOFF),
+            options,
+            predicate,
+        )
+        .await
+    }
+
+    /// Waits until a projection is running.
+    pub async fn wait_until_running<Name>(
+        &self,
+        name: Name,
+        timeout: Duration,
+        options: &GenericProjectionOptions,
+    ) -> crate::Result<ProjectionStatus>
+    where
+        Name: AsRef<str>,
+    {
+        self.wait_until(name, timeout, options, |status| status.status.is_running())
+            .await
+    }
+
+    /// Waits until a projection processed all the events available, that is its progress
+    /// reached 100.
+    pub async fn wait_until_caught_up<Name>(
+        &self,
+        name: Name,
+        timeout: Duration,
+        options: &GenericProjectionOptions,
+    ) -> crate::Result<ProjectionStatus>
+    where
+        Name: AsRef<str>,
+    {
+        self.wait_until(name, timeout, options, |status| status.progress >= 100.0)
+            .await
+    }
+
+    /// Waits until a projection faults and returns the reason.
+    pub async fn wait_until_faulted<Name>(
+        &self,
+        name: Name,
+        timeout: Duration,
+        options: &GenericProjectionOptions,
+    ) -> crate::Result<String>
+    where
+        Name: AsRef<str>,
+    {
+        let status = self
+            .wait_until(name, timeout, options, |status| status.status.is_faulted())
+            .await?;
+
+        Ok(status.state_reason)
+    }
+
+    async fn poll_status<F>(
+        &self,
+        name: &str,
+        timeout: Option<Duration>,
+        (mut backoff, max_backoff): (Duration, Duration),
+        options: &GenericProjectionOptions,
+        mut predicate: F,
+    ) -> crate::Result<ProjectionStatus>
+    where
+        F: FnMut(&ProjectionStatus) -> bool,
+    {
+        let started = Instant::now();
+
+        loop {
+            if let Some(status) = self.get_status(name, options).await? {
+                if predicate(&status) {
+                    return Ok(status);
+                }
+
+                if status.status.is_faulted() {
+                    return Err(crate::Error::ProjectionFaulted {
+                        name: name.to_string(),
+                        reason: status.state_reason,
+                    });
+                }
+            }
+
+            if let Some(timeout) = timeout {
+                let elapsed = started.elapsed();
+
+                if elapsed >= timeout {
+                    return Err(crate::Error::DeadlineExceeded);
+                }
+
+                backoff = backoff.min(timeout - elapsed);
+            }
+
+            tokio::time::sleep(backoff).await;
+            backoff = (backoff * 2).min(max_backoff);
+        }
+    }
+
+    /// Reads the definition of a projection from its `$projections-<name>` stream. Returns `None`
+    /// if the projection doesn't exist.
+    pub async fn get_definition<Name>(
+        &self,
+        name: Name,
+        options: &GenericProjectionOptions,
+    ) -> crate::Result<Option<ProjectionDefinition>>
+    where
+        Name: AsRef<str>,
+    {
+        let read_options = ReadStreamOptions {
+            common_operation_options: options.common_operation_options.clone(),
+            ..ReadStreamOptions::default()
+                .position(crate::StreamPosition::End)
+                .backwards()
+        };
+
+        let stream_name = format!("$projections-{}", name.as_ref());
+        let mut stream = crate::commands::read_stream(
+            self.client.clone(),
+            &read_options,
+            stream_name.as_str(),
+            read_options.max_count as u64,
+        )
+        .await?;
+
+        loop {
+            let event = match stream.next().await {
+                Err(crate::Error::ResourceNotFound) | Ok(None) => return Ok(None),
+                Err(e) => return Err(e),
+                Ok(Some(event)) => event,
+            };
+
+            let event = event.get_original_event();
+            if event.event_type != PROJECTION_UPDATED_EVENT_TYPE {
+                continue;
+            }
+
+            let state = serde_json::from_slice::<PersistedState>(&event.data).map_err(|e| {
+                crate::Error::InternalParsingError(format!(
+                    "projection '{}' definition: {}",
+                    name.as_ref(),
+                    e
+                ))
+            })?;
+
+            let mode = match state.mode {
+                serde_json::Value::String(mode) => parse_infallible(&mode),
+                serde_json::Value::Number(mode) => match mode.as_u64() {
+                    Some(0) => ProjectionMode::Transient,
+                    Some(1) => ProjectionMode::OneTime,
+                    Some(2) => ProjectionMode::Continuous,
+                    _ => ProjectionMode::Unknown(mode.to_string()),
+                },
+                other => ProjectionMode::Unknown(other.to_string()),
+            };
+
+            return Ok(Some(ProjectionDefinition {
+                name: name.as_ref().to_string(),
+                query: state.query,
+                mode,
+                enabled: state.enabled,
+                emit_enabled: state.emit_enabled,
+                track_emitted_streams: state.track_emitted_streams,
+            }));
+        }
+    }
+
+    /// Lists the partitions of a partitioned projection, like the ones using `foreachStream` or
+    /// `partitionBy`, from its `$projections-<name>-partitions` stream, in creation order.
+    pub async fn list_partitions<Name>(
+        &self,
+        name: Name,
+        options: &GenericProjectionOptions,
+    ) -> crate::Result<Vec<String>>
+    where
+        Name: AsRef<str>,
+    {
+        let read_options = ReadStreamOptions {
+            common_operation_options: options.common_operation_options.clone(),
+            ..ReadStreamOptions::default()
+        };
+
+        let stream_name = format!("$projections-{}-partitions", name.as_ref());
+        let mut stream = crate::commands::read_stream(
+            self.client.clone(),
+            &read_options,
+            stream_name.as_str(),
+            read_options.max_count as u64,
+        )
+        .await?;
+
+        let mut seen = HashSet::new();
+        let mut partitions = Vec::new();
+
+        loop {
+            let event = match stream.next().await {
+                Err(crate::Error::ResourceNotFound) | Ok(None) => break,
+                Err(e) => return Err(e),
+                Ok(Some(event)) => event,
+            };
+
+            let data = &event.get_original_event().data;
+            let partition = serde_json::from_slice::<String>(data)
+                .unwrap_or_else(|_| String::from_utf8_lossy(data).into_owned());
+
+            if seen.insert(partition.clone()) {
+                partitions.push(partition);
+            }
+        }
+
+        Ok(partitions)
+    }
+
+    /// Reads the state of many partitions of a projection concurrently. States are returned in
+    /// the order of `partitions`.
+    pub async fn get_partition_states<Name, I, A>(
+        &self,
+        name: Name,
+        partitions: I,
+        options: &GetPartitionStatesOptions,
+    ) -> crate::Result<Vec<(String, serde_json::Result<A>)>>
+    where
+        Name: AsRef<str>,
+        I: IntoIterator,
+        I::Item: AsRef<str>,
+        A: DeserializeOwned + Send,
+    {
+        let name = name.as_ref();
+        let state_options = GetStateProjectionOptions {
+            common_operation_options: options.common_operation_options.clone(),
+            ..Default::default()
+        };
+
+        futures::stream::iter(partitions.into_iter().map(|p| p.as_ref().to_string()))
+            .map(|partition| {
+                let state_options = state_options.clone().partition(partition.as_str());
+
+                async move {
+                    let state = self.get_state(name, &state_options).await?;
+
+                    Ok((partition, state))
+                }
+            })
+            .buffered(options.concurrency)
+            .try_collect()
+            .await
+    }
+
+    /// Subscribes to the results of every partition of a projection, through its
+    /// `$projections-<name>-<partition>-result` streams. It requires the projection to output
+    /// its state, and subscribes to `$all`, which requires admin rights.
+    ///
+    /// Result streams of other projections whose name starts with `<name>-` share that naming
+    /// scheme, so each partition is checked against the projection partitions, see
+    /// [`ProjectionClient::list_partitions`].
+    pub fn subscribe_to_partition_results<Name, A>(
+        &self,
+        name: Name,
+        options: &SubscribeToPartitionResultsOptions,
+    ) -> PartitionResultSubscription<A>
+    where
+        Name: AsRef<str>,
+        A: DeserializeOwned,
+    {
+        let prefix = format!("$projections-{}-", name.as_ref());
+        let filter = SubscriptionFilter::on_stream_name()
+            .regex(format!("^{}.+-result$", escape_regex(prefix.as_str())));
+
+        let mut subscribe_options = SubscribeToAllOptions {
+            common_operation_options: options.common_operation_options.clone(),
+            ..SubscribeToAllOptions::default()
+                .position(options.position)
+                .filter(filter)
+        };
+
+        if let Some(retry) = options.retry {
+            subscribe_options = subscribe_options.retry_options(retry);
+        }
+
+        PartitionResultSubscription {
+            inner: crate::commands::subscribe_to_all(self.client.clone(), &subscribe_options),
+            client: self.clone(),
+            name: name.as_ref().to_string(),
+            options: GenericProjectionOptions {
+                common_operation_options: options.common_operation_options.clone(),
+            },
+            prefix,
+            partitions: HashSet::new(),
+            foreign: HashSet::new(),
+            _marker: PhantomData,
+        }
+    }
+
     async fn create_projection_internal<Opts>(
         &self,
         create_opts: &Opts,
@@ -104,17 +932,24 @@ impl ProjectionClient {
             options: Some(options),
         };
 
-        let req = crate::commands::new_request(self.client.connection_settings(), create_opts, req);
-
         self.client
-            .execute(|handle| async move {
-                let mut client = projections::projections_client::ProjectionsClient::with_origin(
-                    handle.client,
-                    handle.uri,
+            .execute(create_opts, |handle| {
+                let req = crate::commands::new_request(
+                    self.client.connection_settings(),
+                    create_opts,
+                    req.clone(),
                 );
-                let _ = client.create(req).await?;
 
-                Ok(())
+                async move {
+                    let mut client =
+                        projections::projections_client::ProjectionsClient::with_origin(
+                            handle.client,
+                            handle.uri,
+                        );
+                    let _ = client.create(req).await?;
+
+                    Ok(())
+                }
             })
             .await
     }
@@ -145,18 +980,25 @@ impl ProjectionClient {
             options: Some(req_options),
         };
 
-        let req = crate::commands::new_request(self.client.connection_settings(), options, req);
-
         self.client
-            .execute(|handle| async move {
-                let mut client = projections::projections_client::ProjectionsClient::with_origin(
-                    handle.client.clone(),
-                    handle.uri.clone(),
+            .execute_idempotent(options, |handle| {
+                let req = crate::commands::new_request(
+                    self.client.connection_settings(),
+                    options,
+                    req.clone(),
                 );
 
-                let _ = client.update(req).await?;
+                async move {
+                    let mut client =
+                        projections::projections_client::ProjectionsClient::with_origin(
+                            handle.client.clone(),
+                            handle.uri.clone(),
+                        );
 
-                Ok(())
+                    let _ = client.update(req).await?;
+
+                    Ok(())
+                }
             })
             .await
     }
@@ -180,18 +1022,25 @@ impl ProjectionClient {
             options: Some(req_options),
         };
 
-        let req = crate::commands::new_request(self.client.connection_settings(), options, req);
-
         self.client
-            .execute(|handle| async move {
-                let mut client = projections::projections_client::ProjectionsClient::with_origin(
-                    handle.client.clone(),
-                    handle.uri.clone(),
+            .execute(options, |handle| {
+                let req = crate::commands::new_request(
+                    self.client.connection_settings(),
+                    options,
+                    req.clone(),
                 );
 
-                let _ = client.delete(req).await?;
+                async move {
+                    let mut client =
+                        projections::projections_client::ProjectionsClient::with_origin(
+                            handle.client.clone(),
+                            handle.uri.clone(),
+                        );
 
-                Ok(())
+                    let _ = client.delete(req).await?;
+
+                    Ok(())
+                }
             })
             .await
     }
@@ -217,6 +1066,30 @@ impl ProjectionClient {
         self.statistics(StatsFor::AllContinuous, options).await
     }
 
+    /// Lists projections of every mode.
+    pub async fn list_all(
+        &self,
+        options: &GenericProjectionOptions,
+    ) -> crate::Result<BoxStream<'_, crate::Result<ProjectionStatus>>> {
+        self.statistics(StatsFor::AllProjections, options).await
+    }
+
+    /// Lists transient projections.
+    pub async fn list_transient(
+        &self,
+        options: &GenericProjectionOptions,
+    ) -> crate::Result<BoxStream<'_, crate::Result<ProjectionStatus>>> {
+        self.statistics(StatsFor::AllTransient, options).await
+    }
+
+    /// Lists one-time projections.
+    pub async fn list_one_time(
+        &self,
+        options: &GenericProjectionOptions,
+    ) -> crate::Result<BoxStream<'_, crate::Result<ProjectionStatus>>> {
+        self.statistics(StatsFor::AllOneTime, options).await
+    }
+
     async fn statistics(
         &self,
         stats_for: StatsFor,
@@ -226,6 +1099,8 @@ impl ProjectionClient {
             StatsFor::Name(name) => projections::statistics_req::options::Mode::Name(name),
             StatsFor::AllProjections => projections::statistics_req::options::Mode::All(()),
             StatsFor::AllContinuous => projections::statistics_req::options::Mode::Continuous(()),
+            StatsFor::AllTransient => projections::statistics_req::options::Mode::Transient(()),
+            StatsFor::AllOneTime => projections::statistics_req::options::Mode::OneTime(()),
         };
 
         let stats_options = projections::statistics_req::Options { mode: Some(mode) };
@@ -234,64 +1109,70 @@ impl ProjectionClient {
             options: Some(stats_options),
         };
 
-        let req = crate::commands::new_request(self.client.connection_settings(), options, req);
-
         self.client
-            .execute(|handle| async move {
-                let mut client =
-                    projections::projections_client::ProjectionsClient::with_origin(handle.client.clone(), handle.uri.clone());
+            .execute_idempotent(options, |handle| {
+                let req = crate::commands::new_request(
+                    self.client.connection_settings(),
+                    options,
+                    req.clone(),
+                );
 
-                let mut stream = client.statistics(req).await?.into_inner();
+                async move {
+                    let mut client =
+                        projections::projections_client::ProjectionsClient::with_origin(handle.client.clone(), handle.uri.clone());
 
-                let stream = async_stream::stream! {
-                    loop {
-                        match stream.try_next().await {
-                            Err(e) => {
-                                let e = crate::Error::from_grpc(e);
+                    let mut stream = client.statistics(req).await?.into_inner();
 
-                                handle.report_error(&e);
-                                yield Err(e);
-                                break;
-                            }
+                    let stream = async_stream::stream! {
+                        loop {
+                            match stream.try_next().await {
+                                Err(e) => {
+                                    let e = crate::Error::from_grpc(e);
 
-                            Ok(resp) => {
-                                if let Some(resp) = resp {
-                                    let details = resp.details.expect("to be defined");
-                                    let details = ProjectionStatus {
-                                        core_processing_time: details.core_processing_time,
-                                        version: details.version,
-                                        epoch: details.epoch,
-                                        effective_name: details.effective_name,
-                                        writes_in_progress: details.writes_in_progress,
-                                        reads_in_progress: details.reads_in_progress,
-                                        partitions_cached: details.partitions_cached,
-                                        status: details.status,
-                                        state_reason: details.state_reason,
-                                        name: details.name,
-                                        mode: details.mode,
-                                        position: details.position,
-                                        progress: details.progress,
-                                        last_checkpoint: details.last_checkpoint,
-                                        events_processed_after_restart: details.events_processed_after_restart,
-                                        checkpoint_status: details.checkpoint_status,
-                                        buffered_events: details.buffered_events,
-                                        write_pending_events_after_checkpoint: details.write_pending_events_after_checkpoint,
-                                        write_pending_events_before_checkpoint: details.write_pending_events_before_checkpoint,
-                                    };
-
-                                    yield Ok(details);
-                                    continue;
+                                    handle.report_error(&e);
+                                    yield Err(e);
+                                    break;
                                 }
 
-                                break;
+                                Ok(resp) => {
+                                    if let Some(resp) = resp {
+                                        let details = resp.details.expect("to be defined");
+                                        let details = ProjectionStatus {
+                                            core_processing_time: details.core_processing_time,
+                                            version: details.version,
+                                            epoch: details.epoch,
+                                            effective_name: details.effective_name,
+                                            writes_in_progress: details.writes_in_progress,
+                                            reads_in_progress: details.reads_in_progress,
+                                            partitions_cached: details.partitions_cached,
+                                            status: parse_infallible(&details.status),
+                                            state_reason: details.state_reason,
+                                            name: details.name,
+                                            mode: parse_infallible(&details.mode),
+                                            position: parse_infallible(&details.position),
+                                            progress: details.progress,
+                                            last_checkpoint: parse_infallible(&details.last_checkpoint),
+                                            events_processed_after_restart: details.events_processed_after_restart,
+                                            checkpoint_status: parse_infallible(&details.checkpoint_status),
+                                            buffered_events: details.buffered_events,
+                                            write_pending_events_after_checkpoint: details.write_pending_events_after_checkpoint,
+                                            write_pending_events_before_checkpoint: details.write_pending_events_before_checkpoint,
+                                        };
+
+                                        yield Ok(details);
+                                        continue;
+                                    }
+
+                                    break;
+                                }
                             }
                         }
-                    }
-                };
+                    };
 
-                let stream: BoxStream<crate::Result<ProjectionStatus>> = Box::pin(stream);
+                    let stream: BoxStream<crate::Result<ProjectionStatus>> = Box::pin(stream);
 
-                Ok(stream)
+                    Ok(stream)
+                }
             })
             .await
     }
@@ -312,18 +1193,25 @@ impl ProjectionClient {
             options: Some(req_options),
         };
 
-        let req = crate::commands::new_request(self.client.connection_settings(), options, req);
-
         self.client
-            .execute(|handle| async move {
-                let mut client = projections::projections_client::ProjectionsClient::with_origin(
-                    handle.client.clone(),
-                    handle.uri.clone(),
+            .execute_idempotent(options, |handle| {
+                let req = crate::commands::new_request(
+                    self.client.connection_settings(),
+                    options,
+                    req.clone(),
                 );
 
-                let _ = client.enable(req).await?;
+                async move {
+                    let mut client =
+                        projections::projections_client::ProjectionsClient::with_origin(
+                            handle.client.clone(),
+                            handle.uri.clone(),
+                        );
+
+                    let _ = client.enable(req).await?;
 
-                Ok(())
+                    Ok(())
+                }
             })
             .await
     }
@@ -345,18 +1233,25 @@ impl ProjectionClient {
             options: Some(req_options),
         };
 
-        let req = crate::commands::new_request(self.client.connection_settings(), options, req);
-
         self.client
-            .execute(|handle| async move {
-                let mut client = projections::projections_client::ProjectionsClient::with_origin(
-                    handle.client.clone(),
-                    handle.uri.clone(),
+            .execute(options, |handle| {
+                let req = crate::commands::new_request(
+                    self.client.connection_settings(),
+                    options,
+                    req.clone(),
                 );
 
-                let _ = client.reset(req).await?;
+                async move {
+                    let mut client =
+                        projections::projections_client::ProjectionsClient::with_origin(
+                            handle.client.clone(),
+                            handle.uri.clone(),
+                        );
+
+                    let _ = client.reset(req).await?;
 
-                Ok(())
+                    Ok(())
+                }
             })
             .await
     }
@@ -401,18 +1296,25 @@ impl ProjectionClient {
             options: Some(req_options),
         };
 
-        let req = crate::commands::new_request(self.client.connection_settings(), options, req);
-
         self.client
-            .execute(|handle| async move {
-                let mut client = projections::projections_client::ProjectionsClient::with_origin(
-                    handle.client.clone(),
-                    handle.uri.clone(),
+            .execute_idempotent(options, |handle| {
+                let req = crate::commands::new_request(
+                    self.client.connection_settings(),
+                    options,
+                    req.clone(),
                 );
 
-                let _ = client.disable(req).await?;
+                async move {
+                    let mut client =
+                        projections::projections_client::ProjectionsClient::with_origin(
+                            handle.client.clone(),
+                            handle.uri.clone(),
+                        );
+
+                    let _ = client.disable(req).await?;
 
-                Ok(())
+                    Ok(())
+                }
             })
             .await
     }
@@ -435,22 +1337,29 @@ impl ProjectionClient {
             options: Some(req_options),
         };
 
-        let req = crate::commands::new_request(self.client.connection_settings(), options, req);
-
         self.client
-            .execute(|handle| async move {
-                let mut client = projections::projections_client::ProjectionsClient::with_origin(
-                    handle.client.clone(),
-                    handle.uri.clone(),
+            .execute_idempotent(options, |handle| {
+                let req = crate::commands::new_request(
+                    self.client.connection_settings(),
+                    options,
+                    req.clone(),
                 );
 
-                let resp = client.state(req).await?.into_inner();
-                let value = resp
-                    .state
-                    .map(parse_value)
-                    .unwrap_or(serde_json::Value::Null);
+                async move {
+                    let mut client =
+                        projections::projections_client::ProjectionsClient::with_origin(
+                            handle.client.clone(),
+                            handle.uri.clone(),
+                        );
+
+                    let resp = client.state(req).await?.into_inner();
+                    let value = resp
+                        .state
+                        .map(parse_value)
+                        .unwrap_or(serde_json::Value::Null);
 
-                Ok(serde_json::from_value(value))
+                    Ok(serde_json::from_value(value))
+                }
             })
             .await
     }
@@ -473,38 +1382,49 @@ impl ProjectionClient {
             options: Some(req_options),
         };
 
-        let req = crate::commands::new_request(self.client.connection_settings(), options, req);
-
         self.client
-            .execute(|handle| async move {
-                let mut client = projections::projections_client::ProjectionsClient::with_origin(
-                    handle.client.clone(),
-                    handle.uri.clone(),
+            .execute_idempotent(options, |handle| {
+                let req = crate::commands::new_request(
+                    self.client.connection_settings(),
+                    options,
+                    req.clone(),
                 );
 
-                let resp = client.result(req).await?.into_inner();
-                let value = resp
-                    .result
-                    .map(parse_value)
-                    .unwrap_or(serde_json::Value::Null);
+                async move {
+                    let mut client =
+                        projections::projections_client::ProjectionsClient::with_origin(
+                            handle.client.clone(),
+                            handle.uri.clone(),
+                        );
+
+                    let resp = client.result(req).await?.into_inner();
+                    let value = resp
+                        .result
+                        .map(parse_value)
+                        .unwrap_or(serde_json::Value::Null);
 
-                Ok(serde_json::from_value(value))
+                    Ok(serde_json::from_value(value))
+                }
             })
             .await
     }
 
     pub async fn restart_subsystem(&self, options: &GenericProjectionOptions) -> crate::Result<()> {
-        let req = crate::commands::new_request(self.client.connection_settings(), options, ());
-
         self.client
-            .execute(|handle| async {
-                let mut client = projections::projections_client::ProjectionsClient::with_origin(
-                    handle.client,
-                    handle.uri,
-                );
-                let _ = client.restart_subsystem(req).await?;
-
-                Ok(())
+            .execute(options, |handle| {
+                let req =
+                    crate::commands::new_request(self.client.connection_settings(), options, ());
+
+                async move {
+                    let mut client =
+                        projections::projections_client::ProjectionsClient::with_origin(
+                            handle.client,
+                            handle.uri,
+                        );
+                    let _ = client.restart_subsystem(req).await?;
+
+                    Ok(())
+                }
             })
             .await
     }
@@ -618,3 +1538,207 @@ impl From<crate::Client> for ProjectionClient {
         Self { client: src.client }
     }
 }
+
+/// Whether a query projection went through all its events and wrote its result, so the result
+/// can be read.
+fn is_query_done(status: &ProjectionRunStatus, progress: f32) -> bool {
+    let writing = status.details.iter().any(|d| d.starts_with("Writing"));
+
+    !writing && (status.is_completed() || (status.is_stopped() && progress >= 100.0))
+}
+
+/// Identifies the projection created by [`ProjectionClient::run_query`].
+#[derive(Default)]
+struct QueryProjection {
+    /// First line of the query.
+    tag: String,
+    /// One-time projections that existed before the query started.
+    known: HashSet<String>,
+    /// Name of the projection, once found.
+    name: Option<String>,
+}
+
+/// Deletes the projection created by [`ProjectionClient::run_query`], aborting it first in case
+/// it's still running. If the query future is dropped before it could do so, the cleanup runs in
+/// the background.
+struct QueryCleanup {
+    client: Option<ProjectionClient>,
+    target: QueryProjection,
+    options: GenericProjectionOptions,
+}
+
+impl QueryCleanup {
+    fn new(
+        client: ProjectionClient,
+        target: QueryProjection,
+        options: &GenericProjectionOptions,
+    ) -> Self {
+        Self {
+            client: Some(client),
+            target,
+            options: options.clone(),
+        }
+    }
+
+    fn found(&mut self, name: String) {
+        self.target.name = Some(name);
+    }
+
+    async fn run(mut self) {
+        if let Some(client) = self.client.take() {
+            let target = std::mem::take(&mut self.target);
+            cleanup_query(client, target, self.options.clone()).await;
+        }
+    }
+}
+
+impl Drop for QueryCleanup {
+    fn drop(&mut self) {
+        let Some(client) = self.client.take() else {
+            return;
+        };
+
+        let target = std::mem::take(&mut self.target);
+
+        match tokio::runtime::Handle::try_current() {
+            Ok(runtime) => {
+                runtime.spawn(cleanup_query(client, target, self.options.clone()));
+            }
+
+            Err(_) => warn!(
+                "Failed to delete query projection '{}': no runtime",
+                target.name.as_deref().unwrap_or(target.tag.trim())
+            ),
+        }
+    }
+}
+
+async fn cleanup_query(
+    client: ProjectionClient,
+    target: QueryProjection,
+    options: GenericProjectionOptions,
+) {
+    let name = match target.name.clone() {
+        Some(name) => name,
+        None => match client.find_query(&target, &options).await {
+            Ok(Some(name)) => name,
+            Ok(None) => return,
+            Err(e) => {
+                warn!(
+                    "Failed to look for query projection '{}': {}",
+                    target.tag.trim(),
+                    e
+                );
+                return;
+            }
+        },
+    };
+
+    let _ = client.abort(&name, &options).await;
+
+    let delete_options = DeleteProjectionOptions {
+        common_operation_options: options.common_operation_options,
+        ..Default::default()
+    };
+
+    if let Err(e) = client.delete(&name, &delete_options).await {
+        warn!("Failed to delete query projection '{}': {}", name, e);
+    }
+}
+
+#[cfg(test)]
+mod projection_status_tests {
+    use super::*;
+
+    #[test]
+    fn test_query_done_once_results_are_written() {
+        let writing = parse_infallible::<ProjectionRunStatus>("Completed/Stopped/Writing results");
+        let stopped_writing = parse_infallible::<ProjectionRunStatus>("Stopped/Writing results");
+        let completed = parse_infallible::<ProjectionRunStatus>("Completed/Stopped");
+        let stopped = parse_infallible::<ProjectionRunStatus>("Stopped");
+
+        assert!(!is_query_done(&writing, 100.0));
+        assert!(!is_query_done(&stopped_writing, 100.0));
+        assert!(is_query_done(&completed, 100.0));
+        assert!(is_query_done(&stopped, 100.0));
+        assert!(!is_query_done(&stopped, 50.0));
+    }
+
+    #[test]
+    fn test_parse_run_status() {
+        let status = parse_infallible::<ProjectionRunStatus>("Faulted (Enabled)");
+        assert!(status.is_faulted());
+        assert!(status.enabled);
+        assert_eq!(status.to_string(), "Faulted (Enabled)");
+
+        let status = parse_infallible::<ProjectionRunStatus>("Completed/Stopped/Writing results");
+        assert_eq!(status.state, ProjectionState::Completed);
+        assert!(status.is_stopped());
+        assert!(!status.is_completed());
+
+        let status = parse_infallible::<ProjectionRunStatus>("Completed/Stopped");
+        assert!(status.is_completed());
+
+        let status = parse_infallible::<ProjectionRunStatus>("Suspended");
+        assert_eq!(
+            status.state,
+            ProjectionState::Unknown("Suspended".to_string())
+        );
+    }
+
+    #[test]
+    fn test_parse_position() {
+        assert_eq!(
+            parse_infallible::<ProjectionPosition>(""),
+            ProjectionPosition::None
+        );
+
+        assert_eq!(
+            parse_infallible::<ProjectionPosition>("C:1234/P:1200"),
+            ProjectionPosition::All(Position {
+                commit: 1234,
+                prepare: 1200,
+            })
+        );
+
+        assert_eq!(
+            parse_infallible::<ProjectionPosition>("$ce-account: 42; $ce-user: -1"),
+            ProjectionPosition::Streams(vec![
+                ("$ce-account".to_string(), 42),
+                ("$ce-user".to_string(), -1),
+            ])
+        );
+
+        assert_eq!(
+            parse_infallible::<ProjectionPosition>("Phase: 1"),
+            ProjectionPosition::Phase {
+                phase: 1,
+                completed: false,
+            }
+        );
+
+        assert_eq!(
+            parse_infallible::<ProjectionPosition>("Phase: 0 (completed)"),
+            ProjectionPosition::Phase {
+                phase: 0,
+                completed: true,
+            }
+        );
+    }
+
+    #[test]
+    fn test_parse_mode_and_checkpoint_status() {
+        assert_eq!(
+            parse_infallible::<ProjectionMode>("OneTime"),
+            ProjectionMode::OneTime
+        );
+        assert_eq!(
+            parse_infallible::<CheckpointStatus>(""),
+            CheckpointStatus::Idle
+        );
+        assert_eq!(
+            parse_infallible::<CheckpointStatus>("Requested"),
+            CheckpointStatus::Requested
+        );
+    }
+}
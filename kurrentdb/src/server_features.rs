@@ -133,7 +133,7 @@ impl ServerInfo {
 }
 
 pub(crate) async fn supported_methods(
-    client: &HyperClient,
+    client: HyperClient,
     uri: hyper::Uri,
 ) -> Result<ServerInfo, Status> {
     let mut client = ServerFeaturesClient::with_origin(client, uri);
@@ -9,6 +9,8 @@ use crate::event_store;
 use crate::operations::gossip::VNodeState;
 use bytes::Bytes;
 use chrono::{DateTime, Utc};
+use rustls::pki_types::pem::PemObject;
+use rustls::pki_types::{CertificateDer, PrivateKeyDer};
 use serde::de::SeqAccess;
 use serde::{Deserialize, Deserializer, Serialize, Serializer};
 use serde::{de::Visitor, ser::SerializeSeq};
@@ -53,6 +55,61 @@ impl Credentials {
     }
 }
 
+/// X.509 client certificate and private key, used to authenticate commands with mutual TLS.
+///
+/// Identities are registered on a client under a name, which operations then select with their
+/// `tls_identity` option.
+#[derive(Clone)]
+pub struct TlsIdentity {
+    pub(crate) cert_chain: Vec<CertificateDer<'static>>,
+    pub(crate) key: std::sync::Arc<PrivateKeyDer<'static>>,
+}
+
+impl TlsIdentity {
+    /// Creates an identity out of a PEM-encoded certificate chain and private key.
+    pub fn from_pem(cert: impl AsRef<[u8]>, key: impl AsRef<[u8]>) -> crate::Result<Self> {
+        let cert_chain = CertificateDer::pem_slice_iter(cert.as_ref())
+            .collect::<std::result::Result<Vec<_>, _>>()
+            .map_err(|e| Error::InitializationError(format!("Invalid certificate: {}", e)))?;
+
+        if cert_chain.is_empty() {
+            return Err(Error::InitializationError(
+                "No certificate found in PEM data".to_string(),
+            ));
+        }
+
+        let key = PrivateKeyDer::from_pem_slice(key.as_ref())
+            .map_err(|e| Error::InitializationError(format!("Invalid private key: {}", e)))?;
+
+        Ok(Self {
+            cert_chain,
+            key: std::sync::Arc::new(key),
+        })
+    }
+
+    /// Creates an identity out of PEM-encoded certificate chain and private key files.
+    pub fn from_pem_files(
+        cert: impl AsRef<std::path::Path>,
+        key: impl AsRef<std::path::Path>,
+    ) -> crate::Result<Self> {
+        let read = |path: &std::path::Path| {
+            std::fs::read(path).map_err(|e| {
+                Error::InitializationError(format!("Can't read '{}': {}", path.display(), e))
+            })
+        };
+
+        Self::from_pem(read(cert.as_ref())?, read(key.as_ref())?)
+    }
+}
+
+impl std::fmt::Debug for TlsIdentity {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("TlsIdentity")
+            .field("cert_chain", &self.cert_chain.len())
+            .finish_non_exhaustive()
+    }
+}
+
 struct CredsVisitor;
 
 impl<'de> Visitor<'de> for CredsVisitor {
@@ -263,15 +320,41 @@ pub struct RecordedEvent {
 
     /// When the event was created in the database.
     pub created: DateTime<Utc>,
+
+    /// Why a compressed payload couldn't be restored, if it couldn't.
+    pub(crate) compression_error: Option<String>,
 }
 
 impl RecordedEvent {
     pub fn stream_id(&self) -> &str {
         unsafe { std::str::from_utf8_unchecked(&self.stream_id_raw) }
     }
+
+    /// Set when the event was written compressed but couldn't be decompressed, in which case
+    /// [`RecordedEvent::data`] and [`RecordedEvent::custom_metadata`] hold the stored bytes
+    /// rather than the original ones.
+    pub fn compression_error(&self) -> Option<&str> {
+        self.compression_error.as_deref()
+    }
 }
 
 impl RecordedEvent {
+    /// Tells if this event is a link event.
+    pub fn is_link(&self) -> bool {
+        self.event_type == LINK_EVENT_TYPE
+    }
+
+    /// Stream and revision of the event a link event points to. `None` if it's not a link event.
+    pub fn link_target(&self) -> Option<(&str, u64)> {
+        if !self.is_link() {
+            return None;
+        }
+
+        let (revision, stream_name) = std::str::from_utf8(&self.data).ok()?.split_once('@')?;
+
+        Some((stream_name, revision.parse().ok()?))
+    }
+
     /// Tries to decode this event payload as a JSON object.
     pub fn as_json<'a, T>(&'a self) -> serde_json::Result<T>
     where
@@ -318,16 +401,131 @@ impl ResolvedEvent {
     }
 }
 
+/// A page of events returned by a paginated read.
+#[derive(Debug)]
+pub struct Page {
+    /// Events of the page, in read order.
+    pub events: Vec<ResolvedEvent>,
+
+    /// Where the next page starts. `None` when this is the last page.
+    pub next_cursor: Option<PageCursor>,
+
+    /// Indicates no event remains to be read in that direction.
+    pub is_end: bool,
+}
+
+/// Opaque position a paginated read resumes from. Its string form is URL-safe, so it can be
+/// handed to API consumers as-is.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct PageCursor(CursorPosition);
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+enum CursorPosition {
+    Stream(u64),
+    All(Position),
+}
+
+impl PageCursor {
+    pub(crate) fn from_stream_revision(revision: u64) -> Self {
+        Self(CursorPosition::Stream(revision))
+    }
+
+    pub(crate) fn from_position(position: Position) -> Self {
+        Self(CursorPosition::All(position))
+    }
+
+    pub(crate) fn stream_revision(&self) -> crate::Result<u64> {
+        match self.0 {
+            CursorPosition::Stream(revision) => Ok(revision),
+            CursorPosition::All(_) => Err(Error::IllegalStateError(
+                "A cursor of a $all read can't resume a stream read".to_string(),
+            )),
+        }
+    }
+
+    pub(crate) fn position(&self) -> crate::Result<Position> {
+        match self.0 {
+            CursorPosition::All(position) => Ok(position),
+            CursorPosition::Stream(_) => Err(Error::IllegalStateError(
+                "A cursor of a stream read can't resume a $all read".to_string(),
+            )),
+        }
+    }
+}
+
+impl std::fmt::Display for PageCursor {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        use base64::Engine;
+
+        let raw = match self.0 {
+            CursorPosition::Stream(revision) => format!("s:{}", revision),
+            CursorPosition::All(position) => format!("a:{}:{}", position.commit, position.prepare),
+        };
+
+        f.write_str(&base64::engine::general_purpose::URL_SAFE_NO_PAD.encode(raw))
+    }
+}
+
+impl std::str::FromStr for PageCursor {
+    type Err = Error;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        use base64::Engine;
+
+        let invalid = || Error::InternalParsingError(format!("Invalid page cursor '{}'", s));
+        let raw = base64::engine::general_purpose::URL_SAFE_NO_PAD
+            .decode(s)
+            .map_err(|_| invalid())?;
+        let raw = String::from_utf8(raw).map_err(|_| invalid())?;
+        let mut parts = raw.split(':');
+
+        let position = match (parts.next(), parts.next(), parts.next(), parts.next()) {
+            (Some("s"), Some(revision), None, None) => {
+                CursorPosition::Stream(revision.parse().map_err(|_| invalid())?)
+            }
+
+            (Some("a"), Some(commit), Some(prepare), None) => CursorPosition::All(Position {
+                commit: commit.parse().map_err(|_| invalid())?,
+                prepare: prepare.parse().map_err(|_| invalid())?,
+            }),
+
+            _ => return Err(invalid()),
+        };
+
+        Ok(Self(position))
+    }
+}
+
+impl Serialize for PageCursor {
+    fn serialize<S>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for PageCursor {
+    fn deserialize<D>(deserializer: D) -> std::result::Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let raw = String::deserialize(deserializer)?;
+
+        raw.parse().map_err(serde::de::Error::custom)
+    }
+}
+
 /// Represents stream metadata as a series of properties for system data and
 /// user-defined metadata.
 #[derive(Debug, Clone)]
-pub enum StreamMetadataResult {
+pub enum StreamMetadataResult<C = CustomProperties> {
     Deleted,
     NotFound,
-    Success(Box<VersionedMetadata>),
+    Success(Box<VersionedMetadata<C>>),
 }
 
-impl StreamMetadataResult {
+impl<C> StreamMetadataResult<C> {
     pub fn is_deleted(&self) -> bool {
         if let StreamMetadataResult::Deleted = self {
             return true;
@@ -353,15 +551,41 @@ impl StreamMetadataResult {
     }
 }
 
+/// Whether a stream exists, and its current revision when it does.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum StreamStatus {
+    /// The stream doesn't exist, was soft-deleted or has no event left to read.
+    NotFound,
+    /// The stream was hard-deleted.
+    Deleted,
+    /// The stream exists and its last event is at the given revision.
+    Exists(u64),
+}
+
+impl StreamStatus {
+    pub fn exists(&self) -> bool {
+        matches!(self, StreamStatus::Exists(_))
+    }
+
+    /// Revision of the last event of the stream, if it exists.
+    pub fn revision(&self) -> Option<u64> {
+        if let StreamStatus::Exists(revision) = self {
+            return Some(*revision);
+        }
+
+        None
+    }
+}
+
 /// Represents a stream metadata.
 #[derive(Debug, Clone)]
-pub struct VersionedMetadata {
+pub struct VersionedMetadata<C = CustomProperties> {
     pub(crate) stream: String,
     pub(crate) version: u64,
-    pub(crate) metadata: StreamMetadata,
+    pub(crate) metadata: StreamMetadata<C>,
 }
 
-impl VersionedMetadata {
+impl<C> VersionedMetadata<C> {
     /// Metadata's stream.
     pub fn stream_name(&self) -> &str {
         self.stream.as_str()
@@ -373,9 +597,14 @@ impl VersionedMetadata {
     }
 
     /// Metadata properties.
-    pub fn metadata(&self) -> &StreamMetadata {
+    pub fn metadata(&self) -> &StreamMetadata<C> {
         &self.metadata
     }
+
+    /// Consumes the versioned metadata, returning its properties.
+    pub fn into_metadata(self) -> StreamMetadata<C> {
+        self.metadata
+    }
 }
 
 /// Represents the direction of read operation (both from '$all' and a regular
@@ -403,6 +632,11 @@ pub enum ReadStreamStatus<A> {
     Error(ReadStreamError),
 }
 
+const LINK_EVENT_TYPE: &str = "$>";
+
+/// Namespace of the name-based ids given to link events by [`EventData::link_to`].
+const LINK_ID_NAMESPACE: Uuid = Uuid::from_u128(0x6c1f_0b0e_5d2a_4e8b_9a47_3f1d_2c8e_b7a1);
+
 /// Holds data of event about to be sent to the server.
 #[derive(Clone, Debug)]
 pub struct EventData {
@@ -452,6 +686,27 @@ impl EventData {
         }
     }
 
+    /// Creates a link event pointing to the event at the given revision of a stream. Reading a
+    /// stream of links with `resolve_link_tos` returns the events they point to.
+    pub fn link(stream_name: impl AsRef<str>, revision: u64) -> Self {
+        let payload = format!("{}@{}", revision, stream_name.as_ref());
+
+        EventData::binary(LINK_EVENT_TYPE, payload.into())
+    }
+
+    /// Creates a link event pointing to the given event or, if it's a link event itself, to the
+    /// event it points to. Its id is derived from the target, so appending the same link twice to
+    /// a stream, like when replaying a subscription to rebuild an index, is idempotent.
+    pub fn link_to(event: &RecordedEvent) -> Self {
+        let (stream_name, revision) = event
+            .link_target()
+            .unwrap_or((event.stream_id(), event.revision));
+        let link = EventData::link(stream_name, revision);
+        let id = Uuid::new_v5(&LINK_ID_NAMESPACE, &link.payload);
+
+        link.id(id)
+    }
+
     /// Set an id to this event. By default, the id will be generated
     pub fn id(self, value: Uuid) -> Self {
         EventData {
@@ -573,10 +828,15 @@ impl StreamMetadataBuilder {
     }
 }
 
+/// Untyped user-defined stream metadata properties.
+pub type CustomProperties = HashMap<String, serde_json::Value>;
+
 /// Represents stream metadata with strongly types properties for system values
-/// and a dictionary-like interface for custom values.
+/// and custom values. Custom values are a dictionary-like [`CustomProperties`] unless a
+/// user-defined type is provided. Such a type is flattened alongside system values, so its fields
+/// must not start with `$`.
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Default)]
-pub struct StreamMetadata {
+pub struct StreamMetadata<C = CustomProperties> {
     /// A sliding window based on the number of items in the stream. When data reaches
     /// a certain length it disappears automatically from the stream and is considered
     /// eligible for scavenging.
@@ -622,10 +882,55 @@ pub struct StreamMetadata {
     )]
     pub acl: Option<Acl>,
 
-    /// An enumerable of key-value pairs of keys to JSON value for
-    /// user-provided metadata.
-    #[serde(flatten, skip_serializing_if = "HashMap::is_empty", default)]
-    pub custom_properties: HashMap<String, serde_json::Value>,
+    /// User-provided metadata.
+    #[serde(flatten)]
+    pub custom_properties: C,
+}
+
+/// Stream metadata being updated, along with the properties its custom properties type doesn't
+/// model. Those are written back as they were instead of being dropped.
+pub(crate) struct MetadataUpdate<C> {
+    pub(crate) metadata: StreamMetadata<C>,
+    unknown: serde_json::Map<String, serde_json::Value>,
+}
+
+impl<C> MetadataUpdate<C>
+where
+    C: Serialize + serde::de::DeserializeOwned,
+{
+    pub(crate) fn new(metadata: StreamMetadata<C>) -> Self {
+        Self {
+            metadata,
+            unknown: Default::default(),
+        }
+    }
+
+    pub(crate) fn from_untyped(untyped: &StreamMetadata) -> serde_json::Result<Self> {
+        let raw = serde_json::to_value(untyped)?;
+        let metadata = serde_json::from_value::<StreamMetadata<C>>(raw.clone())?;
+        let known = serde_json::to_value(&metadata)?;
+        let unknown = match raw {
+            serde_json::Value::Object(raw) => raw
+                .into_iter()
+                .filter(|(key, _)| known.get(key).is_none())
+                .collect(),
+            _ => Default::default(),
+        };
+
+        Ok(Self { metadata, unknown })
+    }
+
+    pub(crate) fn into_untyped(self) -> serde_json::Result<StreamMetadata> {
+        let mut raw = serde_json::to_value(&self.metadata)?;
+
+        if let serde_json::Value::Object(raw) = &mut raw {
+            for (key, value) in self.unknown {
+                raw.entry(key).or_insert(value);
+            }
+        }
+
+        serde_json::from_value(raw)
+    }
 }
 
 fn serialize_duration<S>(
@@ -741,6 +1046,20 @@ impl StreamMetadata {
     }
 }
 
+impl<C> StreamMetadata<C> {
+    /// Replaces the custom properties, possibly changing their type.
+    pub fn with_custom_properties<D>(self, custom_properties: D) -> StreamMetadata<D> {
+        StreamMetadata {
+            max_count: self.max_count,
+            max_age: self.max_age,
+            truncate_before: self.truncate_before,
+            cache_control: self.cache_control,
+            acl: self.acl,
+            custom_properties,
+        }
+    }
+}
+
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub enum Acl {
     UserStream,
@@ -867,6 +1186,76 @@ pub struct StreamAcl {
     pub meta_write_roles: Option<Vec<String>>,
 }
 
+/// Server-wide settings stored in the `$settings` stream. The default ACLs apply to every stream
+/// that doesn't define its own ACL in its metadata.
+#[derive(Default, Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct SystemSettings {
+    /// Default ACL of user streams.
+    #[serde(
+        rename = "$userStreamAcl",
+        skip_serializing_if = "Option::is_none",
+        default
+    )]
+    pub user_stream_acl: Option<StreamAcl>,
+
+    /// Default ACL of system streams, whose name starts with `$`.
+    #[serde(
+        rename = "$systemStreamAcl",
+        skip_serializing_if = "Option::is_none",
+        default
+    )]
+    pub system_stream_acl: Option<StreamAcl>,
+
+    /// Settings this client doesn't model, written back as they are.
+    #[serde(flatten)]
+    pub other: HashMap<String, serde_json::Value>,
+}
+
+impl SystemSettings {
+    pub fn new() -> Self {
+        SystemSettings::default()
+    }
+
+    /// Sets the default ACL of user streams.
+    pub fn user_stream_acl(self, acl: StreamAcl) -> Self {
+        Self {
+            user_stream_acl: Some(acl),
+            ..self
+        }
+    }
+
+    /// Sets the default ACL of system streams.
+    pub fn system_stream_acl(self, acl: StreamAcl) -> Self {
+        Self {
+            system_stream_acl: Some(acl),
+            ..self
+        }
+    }
+}
+
+/// System settings along with the revision of the `$settings` event they were read from.
+#[derive(Debug, Clone)]
+pub struct VersionedSystemSettings {
+    pub(crate) version: u64,
+    pub(crate) settings: SystemSettings,
+}
+
+impl VersionedSystemSettings {
+    /// Revision of the settings in the `$settings` stream.
+    pub fn version(&self) -> u64 {
+        self.version
+    }
+
+    pub fn settings(&self) -> &SystemSettings {
+        &self.settings
+    }
+
+    /// Consumes the versioned settings, returning the settings.
+    pub fn into_settings(self) -> SystemSettings {
+        self.settings
+    }
+}
+
 fn serialize_roles<S>(
     src: &Option<Vec<String>>,
     serializer: S,
@@ -939,7 +1328,12 @@ where
 mod metadata_tests {
     use std::time::Duration;
 
-    use super::{Acl, StreamAclBuilder, StreamMetadata, StreamMetadataBuilder};
+    use serde::{Deserialize, Serialize};
+
+    use super::{
+        Acl, MetadataUpdate, StreamAclBuilder, StreamMetadata, StreamMetadataBuilder,
+        SystemSettings,
+    };
 
     #[test]
     fn isomorphic_1() -> Result<(), Box<dyn std::error::Error>> {
@@ -1006,6 +1400,138 @@ mod metadata_tests {
         Ok(())
     }
 
+    #[test]
+    fn typed_custom_properties() -> Result<(), Box<dyn std::error::Error>> {
+        #[derive(Serialize, Deserialize, Debug, Default, PartialEq, Eq)]
+        struct Custom {
+            owner: String,
+            #[serde(default)]
+            retention_days: Option<u32>,
+        }
+
+        let content = r#"{ "$maxCount": 12, "owner": "billing", "retention_days": 30 }"#;
+        let actual: StreamMetadata<Custom> = serde_json::from_str(content)?;
+
+        assert_eq!(actual.max_count, Some(12));
+        assert_eq!(
+            actual.custom_properties,
+            Custom {
+                owner: "billing".to_string(),
+                retention_days: Some(30),
+            }
+        );
+
+        let untyped: StreamMetadata = serde_json::from_slice(&serde_json::to_vec(&actual)?)?;
+
+        assert_eq!(untyped.max_count, Some(12));
+        assert_eq!(untyped.custom_properties["owner"], "billing");
+
+        Ok(())
+    }
+
+    #[test]
+    fn metadata_update_keeps_unknown_properties() -> Result<(), Box<dyn std::error::Error>> {
+        #[derive(Serialize, Deserialize, Debug, Default, PartialEq, Eq)]
+        struct Custom {
+            #[serde(default, skip_serializing_if = "Option::is_none")]
+            owner: Option<String>,
+        }
+
+        let untyped = StreamMetadataBuilder::new()
+            .max_count(12)
+            .insert_custom_property("owner", "billing")
+            .insert_custom_property("foreign", serde_json::json!({ "team": "ops" }))
+            .build();
+
+        let mut update = MetadataUpdate::<Custom>::from_untyped(&untyped)?;
+
+        update.metadata.max_count = Some(24);
+        update.metadata.custom_properties.owner = Some("payments".to_string());
+
+        let actual = update.into_untyped()?;
+
+        assert_eq!(actual.max_count, Some(24));
+        assert_eq!(actual.custom_properties["owner"], "payments");
+        assert_eq!(
+            actual.custom_properties["foreign"],
+            serde_json::json!({ "team": "ops" })
+        );
+
+        let mut update = MetadataUpdate::<Custom>::from_untyped(&actual)?;
+
+        update.metadata.custom_properties.owner = None;
+
+        let actual = update.into_untyped()?;
+
+        assert!(!actual.custom_properties.contains_key("owner"));
+        assert!(actual.custom_properties.contains_key("foreign"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn system_settings_keep_unknown_fields() -> Result<(), Box<dyn std::error::Error>> {
+        let content = r#"{ "$systemStreamAcl": { "$r": "$admins" }, "$futureSetting": 42 }"#;
+        let mut settings: SystemSettings = serde_json::from_str(content)?;
+
+        settings.user_stream_acl = Some(StreamAclBuilder::new().add_read_roles("$all").build());
+
+        let actual: serde_json::Value = serde_json::to_value(&settings)?;
+
+        assert_eq!(actual["$futureSetting"], 42);
+        assert_eq!(actual["$systemStreamAcl"]["$r"], "$admins");
+        assert_eq!(actual["$userStreamAcl"]["$r"], "$all");
+
+        Ok(())
+    }
+
+    #[test]
+    fn system_settings_spec() -> Result<(), Box<dyn std::error::Error>> {
+        let content = r#"
+        {
+            "$userStreamAcl": {
+                "$r": "$all",
+                "$w": ["ops", "devs"],
+                "$d": "$admins",
+                "$mr": "$all",
+                "$mw": "$admins"
+            },
+            "$systemStreamAcl": {
+                "$r": "$admins",
+                "$w": "$admins"
+            }
+        }
+        "#;
+
+        let expected = SystemSettings::new()
+            .user_stream_acl(
+                StreamAclBuilder::new()
+                    .add_read_roles("$all")
+                    .add_write_roles("ops")
+                    .add_write_roles("devs")
+                    .add_delete_roles("$admins")
+                    .add_meta_read_roles("$all")
+                    .add_meta_write_roles("$admins")
+                    .build(),
+            )
+            .system_stream_acl(
+                StreamAclBuilder::new()
+                    .add_read_roles("$admins")
+                    .add_write_roles("$admins")
+                    .build(),
+            );
+
+        let actual: SystemSettings = serde_json::from_str(content)?;
+
+        assert_eq!(expected, actual);
+
+        let roundtrip: SystemSettings = serde_json::from_slice(&serde_json::to_vec(&expected)?)?;
+
+        assert_eq!(expected, roundtrip);
+
+        Ok(())
+    }
+
     #[test]
     fn metadata_spec() -> Result<(), Box<dyn std::error::Error>> {
         let content = r#"
@@ -1050,6 +1576,23 @@ mod metadata_tests {
     }
 }
 
+/// Progress of a multi-stream subscription. Persist it to resume the subscription later on.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StreamsCheckpoint {
+    /// Position in the transaction log the subscription has gone through.
+    pub position: Option<Position>,
+
+    /// Revision of the last event seen per stream.
+    pub revisions: HashMap<String, u64>,
+}
+
+impl StreamsCheckpoint {
+    /// Revision of the last event seen from the given stream.
+    pub fn revision(&self, stream_name: &str) -> Option<u64> {
+        self.revisions.get(stream_name).copied()
+    }
+}
+
 /// Events related to a subscription.
 #[derive(Debug)]
 pub enum SubscriptionEvent {
@@ -1337,6 +1880,22 @@ impl std::fmt::Display for NodePreference {
     }
 }
 
+/// Compression algorithm, used for gRPC messages and event payloads.
+#[derive(Copy, Clone, Eq, PartialEq, Debug, Serialize, Deserialize)]
+pub enum CompressionAlgorithm {
+    Gzip,
+    Zstd,
+}
+
+impl std::fmt::Display for CompressionAlgorithm {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            CompressionAlgorithm::Gzip => write!(f, "gzip"),
+            CompressionAlgorithm::Zstd => write!(f, "zstd"),
+        }
+    }
+}
+
 impl NodePreference {
     pub(crate) fn match_preference(&self, state: &VNodeState) -> bool {
         matches!(
@@ -1410,7 +1969,12 @@ pub enum Error {
     #[error("Connection is closed.")]
     ConnectionClosed,
     #[error("Unmapped gRPC error: code: {code}, message: {message}.")]
-    Grpc { code: tonic::Code, message: String },
+    Grpc {
+        code: tonic::Code,
+        message: String,
+        #[source]
+        source: Option<Box<Status>>,
+    },
     #[error("gRPC connection error: {0}")]
     GrpcConnectionError(GrpcConnectionError),
     #[error("Internal parsing error: {0}")]
@@ -1421,8 +1985,12 @@ pub enum Error {
     ResourceAlreadyExists,
     #[error("The resource you asked for doesn't exist")]
     ResourceNotFound,
-    #[error("The resource you asked for was deleted")]
-    ResourceDeleted,
+    #[error("Stream '{stream}' was deleted")]
+    StreamDeleted {
+        stream: String,
+        #[source]
+        source: Option<Box<Status>>,
+    },
     #[error("The operation is unsupported by the server")]
     UnsupportedFeature,
     #[error("Unexpected internal client error. Please fill an issue on GitHub")]
@@ -1433,40 +2001,87 @@ pub enum Error {
     InitializationError(String),
     #[error("Illegal state error: {0}")]
     IllegalStateError(String),
+    #[error("Read limit exceeded: {0}")]
+    ReadLimitExceeded(String),
+    #[error("Invalid filter: {reason}")]
+    InvalidFilter { reason: String },
+    #[error("Invalid stream name '{name}': {reason}")]
+    InvalidStreamName { name: String, reason: String },
+    #[error("Projection '{name}' faulted: {reason}")]
+    ProjectionFaulted { name: String, reason: String },
     #[error("Wrong expected version: expected '{expected}' but got '{current}'")]
     WrongExpectedVersion {
         expected: StreamState,
         current: CurrentRevision,
     },
+    #[error("Maximum append size of {max_append_size} bytes exceeded")]
+    MaximumAppendSizeExceeded {
+        max_append_size: u32,
+        #[source]
+        source: Option<Box<Status>>,
+    },
+    #[error("Invalid transaction")]
+    InvalidTransaction {
+        #[source]
+        source: Option<Box<Status>>,
+    },
+    #[error("Bad request: {message}")]
+    BadRequest {
+        message: String,
+        #[source]
+        source: Option<Box<Status>>,
+    },
+    #[error("Persistent subscription '{group}' on '{stream}' doesn't exist")]
+    PersistentSubscriptionDoesNotExist {
+        stream: String,
+        group: String,
+        #[source]
+        source: Option<Box<Status>>,
+    },
+    #[error("Persistent subscription '{group}' on '{stream}' already exists")]
+    PersistentSubscriptionExists {
+        stream: String,
+        group: String,
+        #[source]
+        source: Option<Box<Status>>,
+    },
+    #[error("Persistent subscription '{group}' on '{stream}' reached its maximum subscriber count")]
+    MaximumSubscribersReached {
+        stream: String,
+        group: String,
+        #[source]
+        source: Option<Box<Status>>,
+    },
+    #[error("Persistent subscription '{group}' on '{stream}' failed: {reason}")]
+    PersistentSubscriptionFailed {
+        stream: String,
+        group: String,
+        reason: String,
+        #[source]
+        source: Option<Box<Status>>,
+    },
+    #[error("User '{login}' doesn't exist")]
+    UserNotFound {
+        login: String,
+        #[source]
+        source: Option<Box<Status>>,
+    },
+    #[error("Scavenge '{scavenge_id}' doesn't exist")]
+    ScavengeNotFound {
+        scavenge_id: String,
+        #[source]
+        source: Option<Box<Status>>,
+    },
 }
 
 impl Error {
     pub fn from_grpc(status: Status) -> Self {
-        let metadata = status.metadata();
-        if let Some("not-leader") = metadata.get("exception").and_then(|e| e.to_str().ok()) {
-            let endpoint = metadata
-                .get("leader-endpoint-host")
-                .zip(metadata.get("leader-endpoint-port"))
-                .and_then(|(host, port)| {
-                    let host = host.to_str().ok()?;
-                    let port = port.to_str().ok()?;
-                    let host = host.to_string();
-                    let port = port.parse().ok()?;
-
-                    Some(Endpoint { host, port })
-                });
-
-            if let Some(leader) = endpoint {
-                return Error::NotLeaderException(leader);
-            }
+        if let Some(e) = event_store::generated::error_from_status_details(&status) {
+            return e;
         }
 
-        if let Some("stream-deleted") = status
-            .metadata()
-            .get("exception")
-            .and_then(|e| e.to_str().ok())
-        {
-            return Error::ResourceDeleted;
+        if let Some(e) = error_from_exception_metadata(&status) {
+            return e;
         }
 
         if status.code() == Code::Cancelled && status.message() == "Timeout expired"
@@ -1506,9 +2121,36 @@ impl Error {
         Error::Grpc {
             code: status.code(),
             message: status.message().to_string(),
+            source: Some(Box::new(status)),
         }
     }
 
+    /// Returns true if the error comes from a temporary condition, like a node going down or
+    /// losing its leadership, that could go away if the command is sent again. The command might
+    /// have been processed by the server though, so only idempotent commands should be retried
+    /// on those errors. An exceeded deadline isn't transient: retrying would only extend it.
+    pub fn is_transient(&self) -> bool {
+        match self {
+            Error::ServerError(_)
+            | Error::NotLeaderException(_)
+            | Error::GrpcConnectionError(_) => true,
+
+            Error::Grpc { code, .. } => matches!(
+                code,
+                Code::Unavailable | Code::ResourceExhausted | Code::Aborted
+            ),
+
+            _ => false,
+        }
+    }
+
+    /// Returns true if the server is known to have rejected the command without processing it,
+    /// making it safe to retry, whether the command is idempotent or not. A connection lost while
+    /// the command was in flight doesn't qualify: the server may have processed it already.
+    pub fn is_retryable(&self) -> bool {
+        matches!(self, Error::NotLeaderException(_))
+    }
+
     pub fn is_access_denied(&self) -> bool {
         if let Error::AccessDenied = self {
             return true;
@@ -1526,6 +2168,347 @@ impl Error {
     }
 }
 
+impl From<Status> for Error {
+    fn from(status: Status) -> Self {
+        Error::from_grpc(status)
+    }
+}
+
+/// Maps the `exception` metadata entry the server attaches to a failed call, along with the
+/// metadata entries that come with it, into a typed error.
+fn error_from_exception_metadata(status: &Status) -> Option<Error> {
+    let metadata = status.metadata();
+    let get = |key: &str| {
+        metadata
+            .get(key)
+            .and_then(|value| value.to_str().ok())
+            .map(|value| value.to_string())
+    };
+
+    let exception = get("exception")?;
+    let source = || Some(Box::new(status.clone()));
+
+    match exception.as_str() {
+        "not-leader" => {
+            let host = get("leader-endpoint-host")?;
+            let port = get("leader-endpoint-port")?.parse().ok()?;
+
+            Some(Error::NotLeaderException(Endpoint { host, port }))
+        }
+
+        "stream-deleted" => Some(Error::StreamDeleted {
+            stream: get("stream-name").unwrap_or_default(),
+            source: source(),
+        }),
+
+        "access-denied" => Some(Error::AccessDenied),
+
+        "wrong-expected-version" => {
+            let expected = match get("expected-version")?.parse::<i64>().ok()? {
+                -2 => StreamState::Any,
+                -1 => StreamState::NoStream,
+                -4 => StreamState::StreamExists,
+                revision if revision >= 0 => StreamState::StreamRevision(revision as u64),
+                _ => return None,
+            };
+
+            let current = match get("actual-version").and_then(|v| v.parse::<i64>().ok()) {
+                Some(revision) if revision >= 0 => CurrentRevision::Current(revision as u64),
+                _ => CurrentRevision::NoStream,
+            };
+
+            Some(Error::WrongExpectedVersion { expected, current })
+        }
+
+        "maximum-append-size-exceeded" => Some(Error::MaximumAppendSizeExceeded {
+            max_append_size: get("maximum-append-size")
+                .and_then(|v| v.parse().ok())
+                .unwrap_or_default(),
+            source: source(),
+        }),
+
+        "invalid-transaction" => Some(Error::InvalidTransaction { source: source() }),
+
+        "persistent-subscription-does-not-exist" => {
+            Some(Error::PersistentSubscriptionDoesNotExist {
+                stream: get("stream-name").unwrap_or_default(),
+                group: get("group-name").unwrap_or_default(),
+                source: source(),
+            })
+        }
+
+        "persistent-subscription-exists" => Some(Error::PersistentSubscriptionExists {
+            stream: get("stream-name").unwrap_or_default(),
+            group: get("group-name").unwrap_or_default(),
+            source: source(),
+        }),
+
+        "maximum-subscribers-reached" => Some(Error::MaximumSubscribersReached {
+            stream: get("stream-name").unwrap_or_default(),
+            group: get("group-name").unwrap_or_default(),
+            source: source(),
+        }),
+
+        "persistent-subscription-failed" => Some(Error::PersistentSubscriptionFailed {
+            stream: get("stream-name").unwrap_or_default(),
+            group: get("group-name").unwrap_or_default(),
+            reason: get("reason").unwrap_or_default(),
+            source: source(),
+        }),
+
+        "user-not-found" => Some(Error::UserNotFound {
+            login: get("login-name").unwrap_or_default(),
+            source: source(),
+        }),
+
+        "scavenge-not-found" => Some(Error::ScavengeNotFound {
+            scavenge_id: get("scavenge-id").unwrap_or_default(),
+            source: source(),
+        }),
+
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod link_tests {
+    use super::{EventData, Position, RecordedEvent};
+
+    fn recorded(stream_name: &str, revision: u64, event: EventData) -> RecordedEvent {
+        RecordedEvent {
+            stream_id_raw: stream_name.to_string().into(),
+            id: uuid::Uuid::new_v4(),
+            revision,
+            event_type: event.metadata["type"].clone(),
+            data: event.payload,
+            metadata: event.metadata,
+            custom_metadata: Default::default(),
+            is_json: false,
+            position: Position::start(),
+            created: Default::default(),
+            compression_error: None,
+        }
+    }
+
+    #[test]
+    fn link_to_follows_links_with_a_deterministic_id() {
+        let event = recorded("order-1", 3, EventData::binary("OrderPlaced", "{}".into()));
+        let link = EventData::link_to(&event);
+
+        assert_eq!(link.payload, "3@order-1");
+        assert_eq!(link.id_opt, EventData::link_to(&event).id_opt);
+        assert!(link.id_opt.is_some());
+
+        let link_to_link = EventData::link_to(&recorded("$ce-order", 0, link.clone()));
+
+        assert_eq!(link_to_link.payload, "3@order-1");
+        assert_eq!(link_to_link.id_opt, link.id_opt);
+
+        let other = recorded("order-1", 4, EventData::binary("OrderShipped", "{}".into()));
+
+        assert_ne!(EventData::link_to(&other).id_opt, link.id_opt);
+    }
+}
+
+#[cfg(test)]
+mod page_cursor_tests {
+    use super::{PageCursor, Position};
+
+    #[test]
+    fn test_cursor_string_roundtrip() {
+        let cursors = [
+            PageCursor::from_stream_revision(42),
+            PageCursor::from_position(Position {
+                commit: 1_234,
+                prepare: 1_200,
+            }),
+        ];
+
+        for cursor in cursors {
+            let encoded = cursor.to_string();
+
+            assert_eq!(encoded.parse::<PageCursor>().unwrap(), cursor);
+            assert!(
+                encoded
+                    .bytes()
+                    .all(|b| b.is_ascii_alphanumeric() || b == b'-' || b == b'_')
+            );
+
+            let json = serde_json::to_string(&cursor).unwrap();
+
+            assert_eq!(serde_json::from_str::<PageCursor>(&json).unwrap(), cursor);
+        }
+    }
+
+    #[test]
+    fn test_cursor_rejects_garbage() {
+        assert!("not a cursor".parse::<PageCursor>().is_err());
+        assert!("czpmb28".parse::<PageCursor>().is_err());
+        assert!(PageCursor::from_stream_revision(1).position().is_err());
+        assert!(
+            PageCursor::from_position(Position::start())
+                .stream_revision()
+                .is_err()
+        );
+    }
+}
+
+#[cfg(test)]
+mod tls_identity_tests {
+    use super::{Error, TlsIdentity};
+
+    #[test]
+    fn from_pem_rejects_missing_certificate() {
+        let result = TlsIdentity::from_pem("", "");
+
+        assert!(matches!(result, Err(Error::InitializationError(_))));
+    }
+
+    #[test]
+    fn from_pem_files_reports_missing_files() {
+        let result = TlsIdentity::from_pem_files("/does/not/exist.crt", "/does/not/exist.key");
+
+        assert!(
+            matches!(result, Err(Error::InitializationError(msg)) if msg.contains("/does/not/exist.crt"))
+        );
+    }
+}
+
+#[cfg(test)]
+mod error_tests {
+    use prost::Message;
+    use tonic::metadata::MetadataMap;
+    use tonic::{Code, Status};
+
+    use super::{CurrentRevision, Error, GrpcConnectionError, StreamState};
+    use crate::event_store::generated::{common, google_rpc};
+
+    fn status_with_metadata(code: Code, entries: &[(&'static str, &'static str)]) -> Status {
+        let mut metadata = MetadataMap::new();
+
+        for (key, value) in entries {
+            metadata.insert(*key, value.parse().unwrap());
+        }
+
+        Status::with_metadata(code, "boom", metadata)
+    }
+
+    #[test]
+    fn stream_deleted_from_metadata() {
+        let status = status_with_metadata(
+            Code::FailedPrecondition,
+            &[("exception", "stream-deleted"), ("stream-name", "foo")],
+        );
+
+        let err = Error::from_grpc(status);
+
+        assert!(matches!(&err, Error::StreamDeleted { stream, .. } if stream == "foo"));
+        assert!(std::error::Error::source(&err).is_some());
+    }
+
+    #[test]
+    fn wrong_expected_version_from_metadata() {
+        let status = status_with_metadata(
+            Code::FailedPrecondition,
+            &[
+                ("exception", "wrong-expected-version"),
+                ("expected-version", "-1"),
+                ("actual-version", "3"),
+            ],
+        );
+
+        assert!(matches!(
+            Error::from_grpc(status),
+            Error::WrongExpectedVersion {
+                expected: StreamState::NoStream,
+                current: CurrentRevision::Current(3),
+            }
+        ));
+    }
+
+    #[test]
+    fn persistent_subscription_does_not_exist_from_metadata() {
+        let status = status_with_metadata(
+            Code::NotFound,
+            &[
+                ("exception", "persistent-subscription-does-not-exist"),
+                ("stream-name", "foo"),
+                ("group-name", "bar"),
+            ],
+        );
+
+        assert!(matches!(
+            Error::from_grpc(status),
+            Error::PersistentSubscriptionDoesNotExist { stream, group, .. } if stream == "foo" && group == "bar"
+        ));
+    }
+
+    #[test]
+    fn maximum_append_size_exceeded_from_details() {
+        let details = google_rpc::Status {
+            code: Code::InvalidArgument as i32,
+            message: "too big".to_string(),
+            details: Some(prost_types::Any {
+                type_url: "type.googleapis.com/event_store.client.MaximumAppendSizeExceeded"
+                    .to_string(),
+                value: common::MaximumAppendSizeExceeded {
+                    max_append_size: 1_024,
+                }
+                .encode_to_vec(),
+            }),
+        };
+
+        let status = Status::with_details(
+            Code::InvalidArgument,
+            "too big",
+            details.encode_to_vec().into(),
+        );
+
+        assert!(matches!(
+            Error::from_grpc(status),
+            Error::MaximumAppendSizeExceeded {
+                max_append_size: 1_024,
+                source: Some(_),
+            }
+        ));
+    }
+
+    #[test]
+    fn retryability_classification() {
+        let not_leader = status_with_metadata(
+            Code::NotFound,
+            &[
+                ("exception", "not-leader"),
+                ("leader-endpoint-host", "node2"),
+                ("leader-endpoint-port", "2113"),
+            ],
+        );
+        let not_leader = Error::from_grpc(not_leader);
+        let unavailable = Error::from_grpc(Status::unavailable("node down"));
+        let connection = Error::GrpcConnectionError(GrpcConnectionError::Grpc("reset".into()));
+        let deleted = Error::from_grpc(status_with_metadata(
+            Code::FailedPrecondition,
+            &[("exception", "stream-deleted"), ("stream-name", "foo")],
+        ));
+
+        assert!(not_leader.is_retryable() && not_leader.is_transient());
+        assert!(!unavailable.is_retryable() && unavailable.is_transient());
+        assert!(!connection.is_retryable() && connection.is_transient());
+        assert!(!deleted.is_retryable() && !deleted.is_transient());
+        assert!(!Error::DeadlineExceeded.is_transient());
+    }
+
+    #[test]
+    fn unmapped_status_keeps_source() {
+        let err = Error::from_grpc(Status::new(Code::OutOfRange, "nope"));
+
+        assert!(matches!(
+            &err,
+            Error::Grpc { code: Code::OutOfRange, source: Some(status), .. } if status.message() == "nope"
+        ));
+    }
+}
+
 #[derive(Error, Debug, Clone)]
 /// KurrentDB command error.
 pub enum GrpcConnectionError {
@@ -1537,10 +2520,21 @@ pub enum GrpcConnectionError {
 
 pub type Result<A> = std::result::Result<A, Error>;
 
+/// How often the server sends a checkpoint while searching for events matching a filter.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default)]
+pub enum FilterWindow {
+    /// Lets the server use its default search window.
+    #[default]
+    Count,
+    /// Searches at most that many events before sending a checkpoint.
+    Max(u32),
+}
+
 #[derive(Debug, Clone)]
 pub struct SubscriptionFilter {
     pub(crate) based_on_stream: bool,
-    pub(crate) max: Option<u32>,
+    pub(crate) window: FilterWindow,
+    pub(crate) checkpoint_interval_multiplier: u32,
     pub(crate) regex: Option<String>,
     pub(crate) prefixes: Vec<String>,
 }
@@ -1549,7 +2543,8 @@ impl SubscriptionFilter {
     pub fn on_stream_name() -> Self {
         SubscriptionFilter {
             based_on_stream: true,
-            max: None,
+            window: FilterWindow::Count,
+            checkpoint_interval_multiplier: 1,
             regex: None,
             prefixes: Vec::new(),
         }
@@ -1562,6 +2557,47 @@ impl SubscriptionFilter {
         temp
     }
 
+    /// Filters streams belonging to the given category, like `account` for `account-123`.
+    pub fn stream_category(category: impl AsRef<str>) -> Self {
+        SubscriptionFilter::stream_categories([category])
+    }
+
+    /// Filters streams belonging to any of the given categories.
+    pub fn stream_categories<I>(categories: I) -> Self
+    where
+        I: IntoIterator,
+        I::Item: AsRef<str>,
+    {
+        categories
+            .into_iter()
+            .fold(SubscriptionFilter::on_stream_name(), |filter, category| {
+                filter.add_prefix(format!("{}-", category.as_ref()))
+            })
+    }
+
+    /// Filters out system streams, whose name starts with `$`.
+    pub fn exclude_system_streams() -> Self {
+        SubscriptionFilter::on_stream_name().regex("^[^\\$]")
+    }
+
+    /// Filters streams whose name is exactly one of the given names.
+    pub fn stream_names<I>(names: I) -> Self
+    where
+        I: IntoIterator,
+        I::Item: AsRef<str>,
+    {
+        SubscriptionFilter::on_stream_name().regex(exact_match_regex(names))
+    }
+
+    /// Filters events whose type is exactly one of the given types.
+    pub fn event_types<I>(event_types: I) -> Self
+    where
+        I: IntoIterator,
+        I::Item: AsRef<str>,
+    {
+        SubscriptionFilter::on_event_type().regex(exact_match_regex(event_types))
+    }
+
     pub fn exclude_system_events(self) -> Self {
         Self {
             regex: Some("^[^\\$].*".to_string()),
@@ -1569,9 +2605,22 @@ impl SubscriptionFilter {
         }
     }
 
+    /// Shorthand for `window(FilterWindow::Max(max))`.
     pub fn max(self, max: u32) -> Self {
+        self.window(FilterWindow::Max(max))
+    }
+
+    /// Sets how many events the server searches before sending a checkpoint. Default:
+    /// `FilterWindow::Count`.
+    pub fn window(self, window: FilterWindow) -> Self {
+        SubscriptionFilter { window, ..self }
+    }
+
+    /// Sends a checkpoint every `multiplier` search windows only, which suits sparse matches on
+    /// a busy `$all` stream. Default: `1`.
+    pub fn checkpoint_interval_multiplier(self, multiplier: u32) -> Self {
         SubscriptionFilter {
-            max: Some(max),
+            checkpoint_interval_multiplier: multiplier,
             ..self
         }
     }
@@ -1587,6 +2636,132 @@ impl SubscriptionFilter {
         self.prefixes.push(prefix.as_ref().to_string());
         self
     }
+
+    /// Checks the filter is one the server accepts. Commands using a filter run that check before
+    /// reaching the server.
+    pub fn validate(&self) -> Result<()> {
+        let invalid = |reason: &str| {
+            Err(Error::InvalidFilter {
+                reason: reason.to_string(),
+            })
+        };
+
+        if self.regex.is_some() && !self.prefixes.is_empty() {
+            return invalid("a filter can't have both a regex and prefixes");
+        }
+
+        if self.regex.as_deref() == Some("") {
+            return invalid("a filter regex can't be empty");
+        }
+
+        if self.regex.as_deref() == Some(EMPTY_EXACT_MATCH_REGEX) {
+            return invalid("a filter needs at least one non-empty stream name or event type");
+        }
+
+        if self.prefixes.iter().any(String::is_empty) {
+            return invalid("a filter prefix can't be empty");
+        }
+
+        if self.window == FilterWindow::Max(0) {
+            return invalid("a filter window must be greater than 0");
+        }
+
+        if self.checkpoint_interval_multiplier == 0 {
+            return invalid("a checkpoint interval multiplier must be greater than 0");
+        }
+
+        Ok(())
+    }
+}
+
+/// What [`exact_match_regex`] produces without any value, or with a single empty one. It only
+/// matches empty strings, so no event ever.
+const EMPTY_EXACT_MATCH_REGEX: &str = "^(?:)$";
+
+fn exact_match_regex<I>(values: I) -> String
+where
+    I: IntoIterator,
+    I::Item: AsRef<str>,
+{
+    let alternatives = values
+        .into_iter()
+        .map(|value| escape_regex(value.as_ref()))
+        .collect::<Vec<_>>();
+
+    format!("^(?:{})$", alternatives.join("|"))
+}
+
+pub(crate) fn escape_regex(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+
+    for c in value.chars() {
+        if "\\.+*?()|[]{}^$".contains(c) {
+            escaped.push('\\');
+        }
+
+        escaped.push(c);
+    }
+
+    escaped
+}
+
+#[cfg(test)]
+mod filter_tests {
+    use super::{Error, FilterWindow, SubscriptionFilter};
+
+    #[test]
+    fn test_typed_constructors() {
+        let filter = SubscriptionFilter::stream_categories(["account", "order"]);
+
+        assert!(filter.based_on_stream);
+        assert_eq!(filter.prefixes, vec!["account-", "order-"]);
+
+        let filter = SubscriptionFilter::event_types(["OrderPlaced", "$metadata"]);
+
+        assert!(!filter.based_on_stream);
+        assert_eq!(
+            filter.regex.as_deref(),
+            Some("^(?:OrderPlaced|\\$metadata)$")
+        );
+
+        let filter = SubscriptionFilter::stream_names(["order-1", "inventory"]);
+
+        assert!(filter.based_on_stream);
+        assert_eq!(filter.regex.as_deref(), Some("^(?:order-1|inventory)$"));
+
+        assert!(
+            SubscriptionFilter::exclude_system_streams()
+                .validate()
+                .is_ok()
+        );
+    }
+
+    #[test]
+    fn test_validation() {
+        let invalid = [
+            SubscriptionFilter::on_stream_name()
+                .regex("^foo")
+                .add_prefix("bar"),
+            SubscriptionFilter::on_stream_name().add_prefix(""),
+            SubscriptionFilter::on_stream_name().window(FilterWindow::Max(0)),
+            SubscriptionFilter::on_stream_name().checkpoint_interval_multiplier(0),
+            SubscriptionFilter::event_types(Vec::<String>::new()),
+            SubscriptionFilter::stream_names([""]),
+        ];
+
+        for filter in invalid {
+            assert!(matches!(
+                filter.validate(),
+                Err(Error::InvalidFilter { .. })
+            ));
+        }
+
+        let filter = SubscriptionFilter::stream_category("account")
+            .max(100)
+            .checkpoint_interval_multiplier(10);
+
+        assert!(filter.validate().is_ok());
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -1925,6 +3100,210 @@ pub trait StreamName {
     fn into_stream_name(self) -> Bytes;
 }
 
+/// A validated stream name, aware of KurrentDB naming conventions. A stream belongs to a category
+/// when its name is made of a category and an id separated by the first `-`, like `account-123`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct StreamId(String);
+
+impl StreamId {
+    /// Validates a stream name. It can't be empty.
+    pub fn new(name: impl Into<String>) -> Result<Self> {
+        let name = name.into();
+
+        if name.is_empty() {
+            return Err(Error::InvalidStreamName {
+                name,
+                reason: "a stream name can't be empty".to_string(),
+            });
+        }
+
+        Ok(Self(name))
+    }
+
+    /// Builds the `category-id` stream name. The category can't contain `-` and neither part can
+    /// be empty.
+    pub fn from_parts(category: impl AsRef<str>, id: impl AsRef<str>) -> Result<Self> {
+        let (category, id) = (category.as_ref(), id.as_ref());
+        let invalid = |reason: &str| {
+            Err(Error::InvalidStreamName {
+                name: format!("{}-{}", category, id),
+                reason: reason.to_string(),
+            })
+        };
+
+        if category.is_empty() || id.is_empty() {
+            return invalid("a category and an id can't be empty");
+        }
+
+        if category.contains('-') {
+            return invalid("a category can't contain '-'");
+        }
+
+        Ok(Self(format!("{}-{}", category, id)))
+    }
+
+    /// Stream of links to the events of every stream of a category, maintained by the
+    /// `$by_category` system projection.
+    pub fn category_stream(category: impl AsRef<str>) -> Self {
+        Self(format!("$ce-{}", category.as_ref()))
+    }
+
+    /// Stream of links to the events of a given type, maintained by the `$by_event_type` system
+    /// projection.
+    pub fn event_type_stream(event_type: impl AsRef<str>) -> Self {
+        Self(format!("$et-{}", event_type.as_ref()))
+    }
+
+    /// Stream of links to the events sharing a correlation id, maintained by the
+    /// `$by_correlation_id` system projection.
+    pub fn correlation_stream(correlation_id: impl AsRef<str>) -> Self {
+        Self(format!("$bc-{}", correlation_id.as_ref()))
+    }
+
+    /// Name of the stream holding this stream metadata.
+    pub fn metadata_stream(&self) -> StreamId {
+        Self(metadata_stream_name(&self.0))
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    /// Category of the stream, if its name follows the `category-id` convention.
+    pub fn category(&self) -> Option<&str> {
+        self.split().map(|(category, _)| category)
+    }
+
+    /// Id of the stream within its category, if its name follows the `category-id` convention.
+    pub fn id(&self) -> Option<&str> {
+        self.split().map(|(_, id)| id)
+    }
+
+    /// Tells if it's a system stream, whose name starts with `$`.
+    pub fn is_system(&self) -> bool {
+        self.0.starts_with('$')
+    }
+
+    /// Tells if it's a metadata stream, whose name starts with `$$`.
+    pub fn is_metadata(&self) -> bool {
+        self.0.starts_with("$$")
+    }
+
+    fn split(&self) -> Option<(&str, &str)> {
+        self.0
+            .split_once('-')
+            .filter(|(category, id)| !category.is_empty() && !id.is_empty())
+    }
+}
+
+impl std::fmt::Display for StreamId {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl std::str::FromStr for StreamId {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        StreamId::new(s)
+    }
+}
+
+impl TryFrom<String> for StreamId {
+    type Error = Error;
+
+    fn try_from(value: String) -> Result<Self> {
+        StreamId::new(value)
+    }
+}
+
+impl From<StreamId> for String {
+    fn from(value: StreamId) -> Self {
+        value.0
+    }
+}
+
+impl AsRef<str> for StreamId {
+    fn as_ref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl StreamName for StreamId {
+    fn into_stream_name(self) -> Bytes {
+        self.0.into()
+    }
+}
+
+impl StreamName for &StreamId {
+    fn into_stream_name(self) -> Bytes {
+        self.0.clone().into()
+    }
+}
+
+impl MetadataStreamName for StreamId {
+    fn into_metadata_stream_name(self) -> Bytes {
+        metadata_stream_name(&self.0).into()
+    }
+}
+
+impl MetadataStreamName for &StreamId {
+    fn into_metadata_stream_name(self) -> Bytes {
+        metadata_stream_name(&self.0).into()
+    }
+}
+
+fn metadata_stream_name(stream_name: &str) -> String {
+    format!("$${}", stream_name)
+}
+
+#[cfg(test)]
+mod stream_id_tests {
+    use super::StreamId;
+
+    #[test]
+    fn test_category_and_id() {
+        let stream = StreamId::new("account-123-abc").unwrap();
+
+        assert_eq!(stream.category(), Some("account"));
+        assert_eq!(stream.id(), Some("123-abc"));
+        assert_eq!(StreamId::from_parts("account", "123-abc").unwrap(), stream);
+
+        let stream = StreamId::new("inventory").unwrap();
+
+        assert_eq!(stream.category(), None);
+        assert_eq!(stream.id(), None);
+    }
+
+    #[test]
+    fn test_validation() {
+        assert!(StreamId::new("").is_err());
+        assert!(StreamId::from_parts("", "1").is_err());
+        assert!(StreamId::from_parts("account", "").is_err());
+        assert!(StreamId::from_parts("bank-account", "1").is_err());
+        assert!(serde_json::from_str::<StreamId>("\"\"").is_err());
+        assert_eq!(
+            serde_json::from_str::<StreamId>("\"account-1\"").unwrap(),
+            StreamId::from_parts("account", "1").unwrap()
+        );
+    }
+
+    #[test]
+    fn test_system_streams() {
+        assert_eq!(StreamId::category_stream("account").as_str(), "$ce-account");
+        assert_eq!(StreamId::event_type_stream("Opened").as_str(), "$et-Opened");
+        assert_eq!(StreamId::correlation_stream("42").as_str(), "$bc-42");
+
+        let metadata = StreamId::new("account-1").unwrap().metadata_stream();
+
+        assert_eq!(metadata.as_str(), "$$account-1");
+        assert!(metadata.is_system());
+        assert!(metadata.is_metadata());
+    }
+}
+
 impl StreamName for Bytes {
     fn into_stream_name(self) -> Bytes {
         self
@@ -1955,7 +3334,7 @@ impl MetadataStreamName for Bytes {
 
 impl MetadataStreamName for &str {
     fn into_metadata_stream_name(self) -> Bytes {
-        format!("$${}", self).to_string().into()
+        metadata_stream_name(self).into()
     }
 }
 
@@ -1,5 +1,6 @@
+use futures::TryStreamExt;
 use kurrentdb::operations;
-use kurrentdb::operations::StatsOptions;
+use kurrentdb::operations::{MaintenanceEvent, StatsOptions, SystemRole, VNodeState};
 use std::time::Duration;
 use tracing::debug;
 
@@ -25,6 +26,21 @@ async fn test_stats(client: &operations::Client) -> kurrentdb::Result<()> {
     Ok(())
 }
 
+async fn test_watch_stats(client: &operations::Client) -> kurrentdb::Result<()> {
+    let options = StatsOptions::default().refresh_time(Duration::from_millis(500));
+
+    let mut watcher = client.watch_stats(&options).await?;
+    let first = watcher.next().await?;
+    let second = watcher.next().await?;
+
+    assert!(first.delta.is_none());
+    assert!(first.statistics.proc.id > 0);
+    assert!(!first.statistics.es.queues.is_empty());
+    assert!(second.delta.is_some_and(|d| d.elapsed > Duration::ZERO));
+
+    Ok(())
+}
+
 async fn test_create_user(
     client: &operations::Client,
     names: &mut names::Generator<'_>,
@@ -169,6 +185,99 @@ async fn test_user_details(
     Ok(())
 }
 
+async fn test_list_users(
+    client: &operations::Client,
+    names: &mut names::Generator<'_>,
+) -> kurrentdb::Result<()> {
+    let login = generate_login(names);
+
+    client
+        .create_user(
+            login.as_str(),
+            names.next().unwrap(),
+            names.next().unwrap(),
+            Vec::new(),
+            &Default::default(),
+        )
+        .await?;
+
+    let mut stream = client.list_users(&Default::default()).await?;
+    let mut logins = Vec::new();
+
+    while let Some(details) = stream.next().await? {
+        logins.push(details.login);
+    }
+
+    assert!(logins.iter().any(|l| l == "admin"));
+    assert!(logins.contains(&login));
+
+    Ok(())
+}
+
+async fn test_user_groups(
+    client: &operations::Client,
+    names: &mut names::Generator<'_>,
+) -> kurrentdb::Result<()> {
+    let login = generate_login(names);
+    let password = names.next().unwrap();
+    let full_name = names.next().unwrap();
+
+    client
+        .create_user(
+            login.as_str(),
+            password.as_str(),
+            full_name.as_str(),
+            vec!["readers".to_string()],
+            &Default::default(),
+        )
+        .await?;
+
+    client
+        .add_user_to_group(login.as_str(), SystemRole::Operations, &Default::default())
+        .await?;
+
+    // Adding twice is a no-op.
+    client
+        .add_user_to_group(login.as_str(), SystemRole::Operations, &Default::default())
+        .await?;
+
+    let details = client
+        .user_details(login.as_str(), &Default::default())
+        .await?
+        .next()
+        .await?
+        .expect("user to exist");
+
+    assert_eq!(details.groups, vec!["readers", "$ops"]);
+    assert_eq!(details.full_name, full_name);
+
+    client
+        .remove_user_from_group(login.as_str(), "readers", &Default::default())
+        .await?;
+
+    let details = client
+        .user_details(login.as_str(), &Default::default())
+        .await?
+        .next()
+        .await?
+        .expect("user to exist");
+
+    assert!(details.is_in_group(SystemRole::Operations));
+    assert!(!details.is_in_group("readers"));
+
+    // Group changes must leave the password untouched, the server checks the current one here.
+    client
+        .change_user_password(
+            login.as_str(),
+            password.as_str(),
+            names.next().unwrap(),
+            &Default::default(),
+        )
+        .await?;
+
+    Ok(())
+}
+
 async fn test_change_user_password(
     client: &operations::Client,
     names: &mut names::Generator<'_>,
@@ -225,6 +334,21 @@ async fn test_merge_indexes(client: &operations::Client) -> kurrentdb::Result<()
     client.merge_indexes(&Default::default()).await
 }
 
+async fn test_merge_indexes_on_each_node(client: &operations::Client) -> kurrentdb::Result<()> {
+    let results = client
+        .on_each_node(|node| async move { node.merge_indexes(&Default::default()).await })
+        .await?;
+
+    assert!(!results.is_empty());
+
+    for (member, result) in results {
+        debug!("merge_indexes on {:?}: {:?}", member.http_end_point, result);
+        result?;
+    }
+
+    Ok(())
+}
+
 async fn test_resign_node(client: &operations::Client) -> kurrentdb::Result<()> {
     client.resign_node(&Default::default()).await
 }
@@ -233,6 +357,49 @@ async fn test_set_node_priority(client: &operations::Client) -> kurrentdb::Resul
     client.set_node_priority(1, &Default::default()).await
 }
 
+async fn test_prepare_node_for_maintenance(client: &operations::Client) -> eyre::Result<()> {
+    let members = client.read_gossip().await?;
+
+    // Resigning the only node of a cluster never elects another leader.
+    if members.len() < 2 {
+        return Ok(());
+    }
+
+    let leader = members
+        .iter()
+        .find(|m| m.is_alive && m.state == VNodeState::Leader)
+        .expect("cluster to have a leader")
+        .http_end_point
+        .clone();
+
+    let events = client
+        .prepare_node_for_maintenance(&leader, &Default::default())
+        .try_collect::<Vec<_>>()
+        .await?;
+
+    debug!("Maintenance progress: {:?}", events);
+
+    assert!(matches!(events.first(), Some(MaintenanceEvent::Started(_))));
+    assert!(
+        events
+            .iter()
+            .any(|e| matches!(e, MaintenanceEvent::LeaderElected(m) if m.http_end_point != leader))
+    );
+    assert!(matches!(events.last(), Some(MaintenanceEvent::Ready)));
+
+    // The former leader is a follower by now, commands must still run on it.
+    let former_leader = client.on_node(&leader);
+
+    former_leader.merge_indexes(&Default::default()).await?;
+    former_leader
+        .set_node_priority(0, &Default::default())
+        .await?;
+
+    assert_eq!(former_leader.current_selected_node().await?, leader);
+
+    Ok(())
+}
+
 async fn test_op_restart_persistent_subscription_subsystem(
     client: &operations::Client,
 ) -> kurrentdb::Result<()> {
@@ -250,6 +417,21 @@ async fn test_scavenge(client: &operations::Client) -> kurrentdb::Result<()> {
     Ok(())
 }
 
+async fn test_track_scavenge(client: &operations::Client) -> eyre::Result<()> {
+    let result = client.start_scavenge(1, 0, &Default::default()).await?;
+    let handle = client.track_scavenge(result.id(), &Default::default());
+
+    assert_eq!(handle.id(), result.id());
+
+    let summary =
+        tokio::time::timeout(Duration::from_secs(60), handle.await_completion()).await??;
+
+    assert_eq!(summary.scavenge_id, result.id());
+    assert!(summary.is_success(), "{:?}", summary);
+
+    Ok(())
+}
+
 async fn test_shutdown(client: &operations::Client) -> kurrentdb::Result<()> {
     client.shutdown(&Default::default()).await
 }
@@ -264,10 +446,17 @@ pub async fn tests(client: kurrentdb::Client) -> eyre::Result<()> {
     test_gossip(client).await?;
     debug!("Complete");
     debug!("Before test_stats…");
-    if let Err(e) = test_stats(client).await {
-        if !e.is_unsupported_feature() {
-            Err(e)?;
-        }
+    if let Err(e) = test_stats(client).await
+        && !e.is_unsupported_feature()
+    {
+        Err(e)?;
+    }
+    debug!("Complete");
+    debug!("Before test_watch_stats…");
+    if let Err(e) = test_watch_stats(client).await
+        && !e.is_unsupported_feature()
+    {
+        Err(e)?;
     }
     debug!("Complete");
     debug!("Before test_create_user…");
@@ -288,6 +477,12 @@ pub async fn tests(client: kurrentdb::Client) -> eyre::Result<()> {
     debug!("Before test_user_details…");
     test_user_details(client, generator).await?;
     debug!("Complete");
+    debug!("Before test_list_users…");
+    test_list_users(client, generator).await?;
+    debug!("Complete");
+    debug!("Before test_user_groups…");
+    test_user_groups(client, generator).await?;
+    debug!("Complete");
     debug!("Before test_change_user_password…");
     test_change_user_password(client, generator).await?;
     debug!("Complete");
@@ -297,18 +492,27 @@ pub async fn tests(client: kurrentdb::Client) -> eyre::Result<()> {
     debug!("Before test_merge_indexes…");
     test_merge_indexes(client).await?;
     debug!("Complete");
+    debug!("Before test_merge_indexes_on_each_node…");
+    test_merge_indexes_on_each_node(client).await?;
+    debug!("Complete");
     debug!("Before test_resign_node…");
     test_resign_node(client).await?;
     debug!("Complete");
     debug!("Before test_set_node_priority…");
     test_set_node_priority(client).await?;
     debug!("Complete");
+    debug!("Before test_prepare_node_for_maintenance…");
+    test_prepare_node_for_maintenance(client).await?;
+    debug!("Complete");
     debug!("Before test_op_restart_persistent_subscription_subsystem…");
     test_op_restart_persistent_subscription_subsystem(client).await?;
     debug!("Complete");
     debug!("Before test_scavenge…");
     test_scavenge(client).await?;
     debug!("Complete");
+    debug!("Before test_track_scavenge…");
+    test_track_scavenge(client).await?;
+    debug!("Complete");
     debug!("Before test_shutdown…");
     test_shutdown(client).await?;
     debug!("Complete");
@@ -1,6 +1,11 @@
 use crate::common::generate_events;
-use kurrentdb::{Client, ProjectionClient};
+use futures::TryStreamExt;
+use kurrentdb::{
+    Client, CreateProjectionOptions, ProjectionClient, ProjectionMode, RunQueryOptions,
+};
 use serde::Deserialize;
+use std::collections::HashSet;
+use std::time::Duration;
 use tracing::{debug, error, warn};
 
 // This is the state of the projection, see tests/fixtures/projection.js.
@@ -24,6 +29,7 @@ struct Baz {
 
 static PROJECTION_FILE: &str = include_str!("../fixtures/projection.js");
 static PROJECTION_UPDATED_FILE: &str = include_str!("../fixtures/projection-updated.js");
+static PROJECTION_PARTITIONED_FILE: &str = include_str!("../fixtures/projection-partitioned.js");
 
 async fn wait_until_projection_status_cc(
     client: &ProjectionClient,
@@ -35,11 +41,11 @@ async fn wait_until_projection_status_cc(
         let result = client.get_status(name, &Default::default()).await?;
 
         if let Some(stats) = result {
-            if stats.status.contains(status) {
+            if stats.status.as_str().contains(status) {
                 break;
             }
 
-            *last_status = stats.status.clone();
+            *last_status = stats.status.to_string();
         }
 
         tokio::time::sleep(std::time::Duration::from_millis(100)).await;
@@ -179,6 +185,51 @@ async fn delete_projection(
 
     Ok(())
 }
+async fn projection_definition(
+    client: &ProjectionClient,
+    gen_name: &mut names::Generator<'_>,
+) -> eyre::Result<()> {
+    let name = gen_name.next().unwrap();
+
+    assert!(
+        client
+            .get_definition(name.as_str(), &Default::default())
+            .await?
+            .is_none()
+    );
+
+    client
+        .create(
+            name.as_str(),
+            PROJECTION_FILE.to_string(),
+            &CreateProjectionOptions::default().emit(true),
+        )
+        .await?;
+
+    wait_until_projection_status_is(client, name.as_str(), "Running").await?;
+
+    client
+        .update(
+            name.as_str(),
+            PROJECTION_UPDATED_FILE.to_string(),
+            &Default::default(),
+        )
+        .await?;
+
+    // The latest update wins.
+    let definition = client
+        .get_definition(name.as_str(), &Default::default())
+        .await?
+        .expect("projection to be defined");
+
+    assert_eq!(definition.name, name);
+    assert_eq!(definition.query, PROJECTION_UPDATED_FILE);
+    assert_eq!(definition.mode, ProjectionMode::Continuous);
+    assert!(definition.enabled);
+
+    Ok(())
+}
+
 async fn update_projection(
     client: &ProjectionClient,
     gen_name: &mut names::Generator<'_>,
@@ -343,6 +394,159 @@ async fn projection_result(
     Ok(())
 }
 
+async fn projection_wait_until(
+    client: &ProjectionClient,
+    gen_name: &mut names::Generator<'_>,
+) -> eyre::Result<()> {
+    let name = gen_name.next().unwrap();
+    let timeout = Duration::from_secs(FIVE_MINS_IN_SECS);
+
+    client
+        .create(
+            name.as_str(),
+            PROJECTION_FILE.to_string(),
+            &Default::default(),
+        )
+        .await?;
+
+    let status = client
+        .wait_until_running(name.as_str(), timeout, &Default::default())
+        .await?;
+
+    assert_eq!(status.mode, ProjectionMode::Continuous);
+
+    client
+        .wait_until_caught_up(name.as_str(), timeout, &Default::default())
+        .await?;
+
+    Ok(())
+}
+
+#[derive(Deserialize, Debug)]
+struct PartitionState {
+    count: f64,
+}
+
+async fn projection_partitions(
+    stream_client: &Client,
+    client: &ProjectionClient,
+    gen_name: &mut names::Generator<'_>,
+) -> eyre::Result<()> {
+    let event_type = gen_name.next().unwrap();
+    let events = generate_events(event_type.as_str(), 5);
+    let stream_name = gen_name.next().unwrap();
+
+    stream_client
+        .append_to_stream(stream_name, &Default::default(), events)
+        .await?;
+
+    let name = gen_name.next().unwrap();
+    let timeout = Duration::from_secs(FIVE_MINS_IN_SECS);
+
+    client
+        .create(
+            name.as_str(),
+            PROJECTION_PARTITIONED_FILE.to_string(),
+            &Default::default(),
+        )
+        .await?;
+
+    client
+        .wait_until_caught_up(name.as_str(), timeout, &Default::default())
+        .await?;
+
+    let partitions = client
+        .list_partitions(name.as_str(), &Default::default())
+        .await?;
+
+    assert!(partitions.contains(&event_type));
+
+    let states = client
+        .get_partition_states::<_, _, PartitionState>(
+            name.as_str(),
+            &partitions,
+            &Default::default(),
+        )
+        .await?;
+
+    let (_, state) = states
+        .into_iter()
+        .find(|(partition, _)| partition == &event_type)
+        .expect("partition state");
+
+    assert_eq!(state?.count, 5.0);
+
+    // Its result streams look like partitions of the first projection, prefixed with `shadow-`.
+    let shadow = format!("{}-shadow", name);
+
+    client
+        .create(
+            shadow.as_str(),
+            PROJECTION_PARTITIONED_FILE.to_string(),
+            &Default::default(),
+        )
+        .await?;
+
+    client
+        .wait_until_caught_up(shadow.as_str(), timeout, &Default::default())
+        .await?;
+
+    let mut subscription = client
+        .subscribe_to_partition_results::<_, PartitionState>(name.as_str(), &Default::default());
+
+    let wait_for_partition = async {
+        loop {
+            let (partition, state) = subscription.next().await?;
+
+            assert!(!partition.starts_with("shadow-"));
+
+            if partition == event_type {
+                return eyre::Ok(state?);
+            }
+        }
+    };
+
+    let state = tokio::time::timeout(timeout, wait_for_partition).await??;
+    assert!(state.count > 0.0);
+
+    Ok(())
+}
+
+async fn projection_run_query(
+    stream_client: &Client,
+    client: &ProjectionClient,
+    gen_name: &mut names::Generator<'_>,
+) -> eyre::Result<()> {
+    let events = generate_events("testing", 10);
+    let stream_name = gen_name.next().unwrap();
+
+    stream_client
+        .append_to_stream(stream_name, &Default::default(), events)
+        .await?;
+
+    let one_time_names = || async {
+        client
+            .list_one_time(&Default::default())
+            .await?
+            .map_ok(|status| status.name)
+            .try_collect::<HashSet<_>>()
+            .await
+    };
+
+    let before = one_time_names().await?;
+    let options = RunQueryOptions::default().timeout(Duration::from_secs(FIVE_MINS_IN_SECS));
+    let result = client
+        .run_query::<State>(PROJECTION_FILE.to_string(), &options)
+        .await??;
+
+    debug!("{:?}", result);
+
+    // The query projection is deleted once its result is read.
+    assert!(one_time_names().await?.is_subset(&before));
+
+    Ok(())
+}
+
 pub async fn tests(client: Client) -> eyre::Result<()> {
     let mut name_gen = names::Generator::default();
     let stream_client = client.clone();
@@ -354,6 +558,9 @@ pub async fn tests(client: Client) -> eyre::Result<()> {
     debug!("before delete_projection...");
     delete_projection(&client, &mut name_gen).await?;
     debug!("passed");
+    debug!("before projection_definition...");
+    projection_definition(&client, &mut name_gen).await?;
+    debug!("passed");
     debug!("before update_projection...");
     update_projection(&client, &mut name_gen).await?;
     debug!("passed");
@@ -372,6 +579,15 @@ pub async fn tests(client: Client) -> eyre::Result<()> {
     debug!("before projection_result...");
     projection_result(&stream_client, &client, &mut name_gen).await?;
     debug!("passed");
+    debug!("before projection_wait_until...");
+    projection_wait_until(&client, &mut name_gen).await?;
+    debug!("passed");
+    debug!("before projection_partitions...");
+    projection_partitions(&stream_client, &client, &mut name_gen).await?;
+    debug!("passed");
+    debug!("before projection_run_query...");
+    projection_run_query(&stream_client, &client, &mut name_gen).await?;
+    debug!("passed");
 
     Ok(())
 }
@@ -2,9 +2,12 @@ use crate::common::{fresh_stream_id, generate_events};
 use chrono::{Datelike, Utc};
 use futures::channel::oneshot;
 use kurrentdb::{
-    Acl, Client, ReadEvent, StreamAclBuilder, StreamMetadataBuilder, StreamMetadataResult,
-    StreamName, StreamPosition, SubscriptionEvent,
+    Acl, Client, CollectLimits, PageCursor, ReadAllOptions, ReadEvent, ReadEventOptions,
+    ReadStreamOptions, StreamAclBuilder, StreamMetadata, StreamMetadataBuilder,
+    StreamMetadataResult, StreamName, StreamPosition, StreamStatus, SubscriptionEvent,
+    UpdateStreamMetadataOptions,
 };
+use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::time::Duration;
 use tracing::{debug, warn};
@@ -184,6 +187,307 @@ async fn test_metadata_not_exist(client: &Client) -> kurrentdb::Result<()> {
     Ok(())
 }
 
+async fn test_update_metadata(client: &Client) -> kurrentdb::Result<()> {
+    #[derive(Serialize, Deserialize, Default, Debug, PartialEq)]
+    struct Owner {
+        #[serde(default)]
+        owner: Option<String>,
+    }
+
+    let stream_id = fresh_stream_id("update_metadata");
+    let options = UpdateStreamMetadataOptions::default();
+
+    client
+        .update_stream_metadata(
+            stream_id.as_str(),
+            &options,
+            |metadata: &mut StreamMetadata| {
+                metadata.max_count = Some(10);
+                metadata
+                    .custom_properties
+                    .insert("foreign".to_string(), serde_json::json!("kept"));
+            },
+        )
+        .await?;
+
+    client
+        .update_stream_metadata(
+            stream_id.as_str(),
+            &options,
+            |metadata: &mut StreamMetadata<Owner>| {
+                metadata.custom_properties = Owner {
+                    owner: Some("billing".to_string()),
+                };
+            },
+        )
+        .await?;
+
+    let actual = client
+        .get_stream_metadata_as::<Owner>(stream_id.as_str(), &Default::default())
+        .await?;
+
+    if let StreamMetadataResult::Success(actual) = actual {
+        assert_eq!(actual.version(), 1);
+        assert_eq!(actual.metadata().max_count, Some(10));
+        assert_eq!(
+            actual.metadata().custom_properties.owner.as_deref(),
+            Some("billing")
+        );
+    } else {
+        panic!("expected stream metadata, got {:?}", actual);
+    }
+
+    let actual = client
+        .get_stream_metadata(stream_id.as_str(), &Default::default())
+        .await?;
+
+    if let StreamMetadataResult::Success(actual) = actual {
+        assert_eq!(actual.metadata().custom_properties["foreign"], "kept");
+    } else {
+        panic!("expected stream metadata, got {:?}", actual);
+    }
+
+    Ok(())
+}
+
+async fn test_collect_read_stream(client: &Client) -> kurrentdb::Result<()> {
+    #[derive(Deserialize)]
+    struct Payload {
+        event_index: usize,
+    }
+
+    let stream_id = fresh_stream_id("collect_read_stream");
+    let events = generate_events("collect-test", 5);
+
+    let _ = client
+        .append_to_stream(stream_id.as_str(), &Default::default(), events)
+        .await?;
+
+    let options = ReadStreamOptions::default();
+    let collected = client
+        .read_stream(stream_id.as_str(), &options)
+        .await?
+        .collect_as::<Payload>()
+        .await?;
+
+    let indexes = collected
+        .events
+        .iter()
+        .map(|p| p.event_index)
+        .collect::<Vec<_>>();
+    assert_eq!(indexes, vec![1, 2, 3, 4, 5]);
+
+    let collected = client
+        .read_stream(stream_id.as_str(), &options)
+        .await?
+        .collect_while(|e| e.get_original_event().revision < 2)
+        .await?;
+    assert_eq!(collected.events.len(), 2);
+
+    let sum = client
+        .read_stream(stream_id.as_str(), &options)
+        .await?
+        .try_fold(0, |acc, e| {
+            Ok::<_, kurrentdb::Error>(acc + e.get_original_event().revision)
+        })
+        .await?;
+    assert_eq!(sum, 10);
+
+    let result = client
+        .read_stream(stream_id.as_str(), &options)
+        .await?
+        .collect_limits(CollectLimits::default().max_events(3))
+        .collect()
+        .await;
+    assert!(matches!(
+        result,
+        Err(kurrentdb::Error::ReadLimitExceeded(_))
+    ));
+
+    Ok(())
+}
+
+async fn test_read_single_events(client: &Client) -> kurrentdb::Result<()> {
+    let stream_id = fresh_stream_id("read_single_events");
+    let options = ReadEventOptions::default();
+
+    assert_eq!(
+        client
+            .get_stream_status(stream_id.as_str(), &Default::default())
+            .await?,
+        StreamStatus::NotFound
+    );
+    assert!(
+        client
+            .read_last_event(stream_id.as_str(), &options)
+            .await?
+            .is_none()
+    );
+
+    let events = generate_events("read-single-test", 3);
+
+    let _ = client
+        .append_to_stream(stream_id.as_str(), &Default::default(), events)
+        .await?;
+
+    let event = client.read_event(stream_id.as_str(), 1, &options).await?;
+    assert_eq!(event.map(|e| e.get_original_event().revision), Some(1));
+
+    let event = client.read_event(stream_id.as_str(), 10, &options).await?;
+    assert!(event.is_none());
+
+    let event = client.read_last_event(stream_id.as_str(), &options).await?;
+    assert_eq!(event.map(|e| e.get_original_event().revision), Some(2));
+
+    assert_eq!(
+        client
+            .get_stream_status(stream_id.as_str(), &Default::default())
+            .await?,
+        StreamStatus::Exists(2)
+    );
+
+    client
+        .tombstone_stream(stream_id.as_str(), &Default::default())
+        .await?;
+
+    assert_eq!(
+        client
+            .get_stream_status(stream_id.as_str(), &Default::default())
+            .await?,
+        StreamStatus::Deleted
+    );
+
+    Ok(())
+}
+
+async fn test_read_stream_pages(client: &Client) -> kurrentdb::Result<()> {
+    let stream_id = fresh_stream_id("read_stream_pages");
+    let events = generate_events("paging-test", 5);
+
+    let _ = client
+        .append_to_stream(stream_id.as_str(), &Default::default(), events)
+        .await?;
+
+    for (options, expected) in [
+        (
+            ReadStreamOptions::default(),
+            vec![vec![0, 1], vec![2, 3], vec![4]],
+        ),
+        (
+            ReadStreamOptions::default().position(StreamPosition::End),
+            vec![vec![4, 3], vec![2, 1], vec![0]],
+        ),
+        (
+            ReadStreamOptions::default()
+                .backwards()
+                .position(StreamPosition::Position(3)),
+            vec![vec![3, 2], vec![1, 0]],
+        ),
+    ] {
+        let mut cursor: Option<PageCursor> = None;
+        let mut pages = Vec::new();
+
+        loop {
+            let page = client
+                .read_stream_page(stream_id.as_str(), &options, cursor.as_ref(), 2)
+                .await?;
+
+            pages.push(
+                page.events
+                    .iter()
+                    .map(|e| e.get_original_event().revision)
+                    .collect::<Vec<_>>(),
+            );
+
+            if page.is_end {
+                break;
+            }
+
+            // Cursors are meant to go through API consumers.
+            cursor = Some(page.next_cursor.unwrap().to_string().parse()?);
+        }
+
+        assert_eq!(pages, expected);
+    }
+
+    Ok(())
+}
+
+async fn test_read_all_pages(client: &Client) -> kurrentdb::Result<()> {
+    let stream_id = fresh_stream_id("read_all_pages");
+    // The last event only marks where the backward reads start.
+    let events = generate_events("paging-test", 6);
+
+    let _ = client
+        .append_to_stream(stream_id.as_str(), &Default::default(), events)
+        .await?;
+
+    let mut stream = client
+        .read_stream(stream_id.as_str(), &Default::default())
+        .await?;
+    let mut positions = Vec::new();
+
+    while let Some(event) = stream.next().await? {
+        positions.push(event.get_original_event().position);
+    }
+
+    let is_last = |event: &kurrentdb::ResolvedEvent, revision: u64| {
+        let event = event.get_original_event();
+        event.stream_id() == stream_id && event.revision == revision
+    };
+
+    for (options, last) in [
+        (
+            ReadAllOptions::default().position(StreamPosition::Position(positions[0])),
+            4,
+        ),
+        (
+            ReadAllOptions::default()
+                .backwards()
+                .position(StreamPosition::Position(positions[5])),
+            0,
+        ),
+    ] {
+        // Other streams can have events in between, the pages must hold exactly what a single
+        // read returns.
+        let mut expected = Vec::new();
+        let mut stream = client.read_all(&options).await?;
+
+        while let Some(event) = stream.next().await? {
+            expected.push(event.get_original_event().position);
+
+            if is_last(&event, last) {
+                break;
+            }
+        }
+
+        let mut cursor: Option<PageCursor> = None;
+        let mut paged = Vec::new();
+
+        'pages: loop {
+            let page = client.read_all_page(&options, cursor.as_ref(), 2).await?;
+
+            for event in page.events.iter() {
+                paged.push(event.get_original_event().position);
+
+                if is_last(event, last) {
+                    break 'pages;
+                }
+            }
+
+            assert!(
+                !page.is_end,
+                "reached the end of $all before the last event"
+            );
+            cursor = page.next_cursor;
+        }
+
+        assert_eq!(paged, expected);
+    }
+
+    Ok(())
+}
+
 // We check to see the client can handle the correct GRPC proto response when
 // a stream does not exist
 async fn test_read_stream_events_non_existent(client: &Client) -> kurrentdb::Result<()> {
@@ -237,7 +541,7 @@ async fn test_tombstone_stream(client: &Client) -> kurrentdb::Result<()> {
         .read_stream(stream_id.as_str(), &Default::default())
         .await;
 
-    if let Err(kurrentdb::Error::ResourceDeleted) = result {
+    if let Err(kurrentdb::Error::StreamDeleted { .. }) = result {
         Ok(())
     } else {
         panic!("Expected stream deleted error");
@@ -375,6 +679,164 @@ async fn test_subscription_all_filter(client: &Client) -> kurrentdb::Result<()>
     Ok(())
 }
 
+async fn test_subscribe_to_streams(client: &Client) -> kurrentdb::Result<()> {
+    let order = fresh_stream_id("multi_order");
+    let customer = fresh_stream_id("multi_customer");
+    let unrelated = fresh_stream_id("multi_unrelated");
+
+    for stream in [&order, &customer, &unrelated, &order] {
+        let _ = client
+            .append_to_stream(
+                stream.as_str(),
+                &Default::default(),
+                generate_events("multi-stream-test", 1),
+            )
+            .await?;
+    }
+
+    let options = kurrentdb::SubscribeToStreamsOptions::default();
+    let mut sub = client
+        .subscribe_to_streams([order.as_str(), customer.as_str()], &options)
+        .await;
+
+    let (streams, checkpoint) = tokio::time::timeout(Duration::from_secs(60), async move {
+        let mut streams = Vec::new();
+
+        for _ in 0..3 {
+            let event = sub.next().await?;
+            streams.push(event.get_original_stream_id().to_string());
+        }
+
+        Ok::<_, kurrentdb::Error>((streams, sub.checkpoint().clone()))
+    })
+    .await
+    .expect("we are supposed to receive events from that subscription")?;
+
+    assert_eq!(
+        streams,
+        vec![order.clone(), customer.clone(), order.clone()]
+    );
+    assert_eq!(checkpoint.revision(&order), Some(1));
+    assert_eq!(checkpoint.revision(&customer), Some(0));
+    assert_eq!(checkpoint.revision(&unrelated), None);
+
+    let mut sub = client
+        .subscribe_to_streams(Vec::<String>::new(), &options)
+        .await;
+
+    assert!(matches!(
+        sub.next().await,
+        Err(kurrentdb::Error::InvalidFilter { .. })
+    ));
+
+    Ok(())
+}
+
+async fn test_append_links(client: &Client) -> kurrentdb::Result<()> {
+    let stream_id = fresh_stream_id("linked");
+    let index_id = fresh_stream_id("index");
+
+    let _ = client
+        .append_to_stream(
+            stream_id.as_str(),
+            &Default::default(),
+            generate_events("link-test", 3),
+        )
+        .await?;
+
+    let events = client
+        .read_stream(stream_id.as_str(), &Default::default())
+        .await?
+        .collect()
+        .await?
+        .events;
+    let linked = events
+        .iter()
+        .map(|e| e.get_original_event())
+        .filter(|e| e.revision != 1);
+
+    let _ = client
+        .append_links(index_id.as_str(), &Default::default(), linked)
+        .await?;
+
+    let options = ReadStreamOptions::default().resolve_link_tos();
+    let resolved = client
+        .read_stream(index_id.as_str(), &options)
+        .await?
+        .collect()
+        .await?
+        .events;
+
+    let targets = resolved
+        .iter()
+        .map(|e| {
+            let link = e.link.as_ref().expect("a link event");
+            let event = e.event.as_ref().expect("a resolved event");
+
+            assert_eq!(
+                link.link_target(),
+                Some((stream_id.as_str(), event.revision))
+            );
+
+            event.revision
+        })
+        .collect::<Vec<_>>();
+
+    assert_eq!(targets, vec![0, 2]);
+
+    // Replaying the links, like when rebuilding an index, neither links to links nor duplicates.
+    let links = resolved
+        .iter()
+        .map(|e| e.link.as_ref().expect("a link event"));
+
+    let _ = client
+        .append_links(index_id.as_str(), &Default::default(), links)
+        .await?;
+
+    let index = client
+        .read_stream(index_id.as_str(), &Default::default())
+        .await?
+        .collect()
+        .await?
+        .events;
+
+    assert_eq!(index.len(), 2);
+
+    Ok(())
+}
+
+#[cfg(feature = "compression")]
+async fn test_compressed_events(client: &Client) -> kurrentdb::Result<()> {
+    use kurrentdb::{EventData, PayloadCompression};
+
+    let stream_id = fresh_stream_id("compressed");
+    let body = "lorem ipsum ".repeat(1_000);
+    let event = EventData::json("document-uploaded", &serde_json::json!({ "body": body }))
+        .unwrap()
+        .metadata_as_json(&serde_json::json!({ "source": "tests" }))
+        .unwrap()
+        .compress(&PayloadCompression::zstd())
+        .unwrap();
+
+    let _ = client
+        .append_to_stream(stream_id.as_str(), &Default::default(), event)
+        .await?;
+
+    let event = client
+        .read_last_event(stream_id.as_str(), &Default::default())
+        .await?
+        .expect("the compressed event");
+    let event = event.get_original_event();
+    let value = event.as_json::<serde_json::Value>().unwrap();
+    let metadata = serde_json::from_slice::<serde_json::Value>(&event.custom_metadata).unwrap();
+
+    assert!(event.is_json);
+    assert_eq!(value["body"], body);
+    assert_eq!(metadata["source"], "tests");
+
+    Ok(())
+}
+
 async fn test_batch_append(client: &Client) -> kurrentdb::Result<()> {
     let batch_client = client.batch_append(&Default::default()).await?;
 
@@ -540,8 +1002,8 @@ async fn test_read_all_filter(client: &Client) -> kurrentdb::Result<()> {
 
     // TEST 3: Regular expression pattern matching
     debug!("Testing regex pattern matching");
-    let regex_filter = kurrentdb::SubscriptionFilter::on_event_type()
-        .regex(&format!("{}.*include", unique_prefix));
+    let regex_filter =
+        kurrentdb::SubscriptionFilter::on_event_type().regex(format!("{}.*include", unique_prefix));
     let regex_options = kurrentdb::ReadAllOptions::default()
         .filter(regex_filter)
         .max_count(100);
@@ -601,6 +1063,18 @@ pub async fn tests(client: Client) -> eyre::Result<()> {
         test_read_stream_populates_log_position(&client).await?;
     }
     debug!("Complete");
+    debug!("Before test_collect_read_stream");
+    test_collect_read_stream(&client).await?;
+
+    debug!("Before test_read_single_events");
+    test_read_single_events(&client).await?;
+
+    debug!("Before test_read_stream_pages");
+    test_read_stream_pages(&client).await?;
+
+    debug!("Before test_read_all_pages");
+    test_read_all_pages(&client).await?;
+
     debug!("Before test_read_stream_events_non_existent");
     test_read_stream_events_non_existent(&client).await?;
     debug!("Complete");
@@ -609,6 +1083,9 @@ pub async fn tests(client: Client) -> eyre::Result<()> {
     debug!("Complete");
     debug!("Before test test_metadata_not_exist");
     test_metadata_not_exist(&client).await?;
+
+    debug!("Before test test_update_metadata");
+    test_update_metadata(&client).await?;
     debug!("Complete");
     debug!("Before test_delete_stream…");
     test_delete_stream(&client).await?;
@@ -625,6 +1102,18 @@ pub async fn tests(client: Client) -> eyre::Result<()> {
     debug!("Before test_subscription_all_filter…");
     test_subscription_all_filter(&client).await?;
     debug!("Complete");
+    debug!("Before test_subscribe_to_streams…");
+    test_subscribe_to_streams(&client).await?;
+
+    debug!("Before test_append_links");
+    test_append_links(&client).await?;
+
+    #[cfg(feature = "compression")]
+    {
+        debug!("Before test_compressed_events");
+        test_compressed_events(&client).await?;
+    }
+
     debug!("Before test_batch_append");
     if let Err(e) = test_batch_append(&client).await {
         if let kurrentdb::Error::UnsupportedFeature = e {
@@ -0,0 +1,14 @@
+fromAll()
+.partitionBy(function(event){
+	return event.eventType;
+})
+.when({
+	$init:function(){
+		return {
+			count: 0
+		}
+	},
+	$any: function(state, event){
+		state.count += 1;
+	}
+}).outputState()